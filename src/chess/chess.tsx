@@ -1,9 +1,64 @@
-import init, { moves, move_piece, validate, best_move } from "../../wasm/pkg/chess_wasm.js"
+import init, {
+  moves, move_piece, validate, best_move, evaluate,
+  ponder_start, ponder_step, ponder_hit, ponder_stop,
+} from "../../wasm/pkg/chess_wasm.js"
 
 interface Move {
   to: String,
   from: String,
-  promotion: String
+  promotion: String,
+  score?: number | null, // centipawns for the side to move
+  mate?: number | null,  // moves to mate, negative when being mated
+  ponder?: string | null, // the reply expected, in UCI
+}
+
+// style of play, see each field for its unit
+interface EngineConfig {
+  // all in centipawns, scored to the nearest half pawn
+  aggressiveness?: number, // per piece bearing on the enemy king
+  materialism?: number,    // what a pawn of material counts for, 100 as usual
+  contempt?: number,       // what a draw costs, stalemate and the fifty move rule
+  simplification?: number, // most for trading down when ahead
+}
+
+// the side to move's clock in milliseconds, movestogo left out for sudden death
+interface Clock {
+  time: number,
+  increment?: number, // added after each move
+  delay?: number,     // free time before the clock runs
+  movestogo?: number,
+}
+
+// a skill level from 0 to 20, or a rating the engine plays near
+interface EngineOptions {
+  skill?: number,
+  elo?: number,
+  config?: EngineConfig | string,
+  nnue?: boolean, // false to leave a loaded network unused
+  clock?: Clock,
+}
+
+// each side's share of one evaluation term in centipawns
+interface EvaluationTerm {
+  // material, piece_square, pawn_structure, castled_king, king_attack,
+  // mobility, ahead, simplification or fifty_moves
+  name: string,
+  // material, piece_square, pawn_structure, king_safety, mobility or endgame
+  group: string,
+  white: number,
+  black: number,
+}
+
+interface PhaseEvaluation {
+  score: number | null, // centipawns for the side to move
+  mate: number | null,  // 0 when the side to move is checkmated
+  terms: EvaluationTerm[],
+}
+
+interface Evaluation extends PhaseEvaluation {
+  phase: string, // opening, middlegame or endgame
+  // the same position scored as though in each phase
+  phases: { opening: PhaseEvaluation, middlegame: PhaseEvaluation, endgame: PhaseEvaluation },
 }
 
 interface MoveQueue {
@@ -59,10 +114,33 @@ class Chess {
     }
   }
 
-  best_move(): Move {
+  evaluate(options?: EngineOptions): Evaluation {
+    return evaluate(this._fen, options) as Evaluation;
+  }
+
+  /* Pondering on the position after our move and the expected
+   * reply, in slices so a worker can take messages in between
+   */
+  ponder_start(fen: string) {
+    ponder_start(fen);
+  }
+
+  ponder_step(ms: number): number {
+    return ponder_step(ms);
+  }
+
+  ponder_hit(options?: EngineOptions): Move {
+    return ponder_hit(options) as Move;
+  }
+
+  ponder_stop() {
+    ponder_stop();
+  }
+
+  best_move(options?: EngineOptions): Move {
     try {
       let result: Move;
-      result = best_move(this._fen) as Move;
+      result = best_move(this._fen, options) as Move;
       // try to eliminate repeating moves
       let stack_copy = [
         this._stack[this._stack.length - 1].split(" ")[0],
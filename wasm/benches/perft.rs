@@ -0,0 +1,19 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use chess_wasm::game::{fen, perft::perft};
+
+fn bench_perft(c: &mut Criterion) {
+    let positions = [
+        ("startpos", "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", 4),
+        ("kiwipete", "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1", 3),
+        ("endgame", "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1", 5),
+    ];
+    for (name, fen, depth) in positions {
+        let game = fen::decode(fen).unwrap();
+        c.bench_function(&format!("perft {} {}", name, depth), |b| {
+            b.iter(|| perft(black_box(&game), depth, None).unwrap())
+        });
+    }
+}
+
+criterion_group!(benches, bench_perft);
+criterion_main!(benches);
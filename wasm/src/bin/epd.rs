@@ -0,0 +1,57 @@
+/* Runs the engine over an EPD test suite
+ * cargo run --profile perft --bin epd -- [FILE] --depth [N] --time [MS] --threads [N]
+ */
+
+use std::{fs, process, time::Duration};
+use chess_wasm::{
+    engine::{run_suite, SearchLimits},
+    game::epd,
+};
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let mut path: Option<String> = None;
+    let mut limits = SearchLimits::default();
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--depth" => {
+                limits.depth = args.next().and_then(|d| d.parse().ok());
+                if limits.depth.is_none() { usage(); }
+            },
+            "--time" => {
+                let ms = args.next().and_then(|t| t.parse().ok()).unwrap_or_else(|| usage());
+                limits.movetime = Some(Duration::from_millis(ms));
+            },
+            "--threads" => {
+                limits.threads = args.next().and_then(|t| t.parse().ok());
+                if limits.threads.is_none() { usage(); }
+            },
+            _ if path.is_none() => path = Some(arg),
+            _ => usage(),
+        }
+    }
+
+    let path = path.unwrap_or_else(|| usage());
+    let text = fs::read_to_string(&path).unwrap_or_else(|e| {
+        eprintln!("{}: {}", path, e);
+        process::exit(1);
+    });
+
+    let mut suite = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        if line.trim().is_empty() || line.starts_with('#') { continue; }
+        match epd::decode(line) {
+            Ok(e) => suite.push(e),
+            Err(e) => eprintln!("{}:{}: {}", path, i + 1, e),
+        }
+    }
+
+    println!("{}", run_suite(&suite, &limits));
+}
+
+fn usage() -> ! {
+    eprintln!("usage: epd [FILE] [--depth N] [--time MS] [--threads N]");
+    process::exit(2);
+}
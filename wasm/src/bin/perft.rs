@@ -0,0 +1,83 @@
+/* Counts move tree nodes, output matches Stockfish's `go perft`
+ * cargo run --profile perft --bin perft -- --depth [N] [--fen FEN]
+ *     [--divide] [--stats] [--threads N] [--hash MB]
+ */
+
+use std::{process, time::Instant};
+use chess_wasm::game::{
+    fen,
+    perft::{divide, perft_stats, PerftTable},
+};
+
+const START: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+fn main() {
+    let mut fen = String::from(START);
+    let mut depth: Option<u32> = None;
+    let mut show_divide = false;
+    let mut show_stats = false;
+    let mut threads = 1;
+    let mut hash = 0;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--fen" => fen = args.next().unwrap_or_else(|| usage()),
+            "--depth" => depth = Some(number(args.next()) as u32),
+            "--divide" => show_divide = true,
+            "--stats" => show_stats = true,
+            "--threads" => threads = number(args.next()),
+            "--hash" => hash = number(args.next()),
+            _ => usage(),
+        }
+    }
+
+    let depth = depth.filter(|d| *d > 0).unwrap_or_else(|| usage());
+    let game = fen::decode(&fen).unwrap_or_else(|e| fail(e));
+    let table = if hash > 0 { Some(PerftTable::new(hash)) } else { None };
+
+    let pool = rayon::ThreadPoolBuilder::new().num_threads(threads.max(1)).build()
+        .unwrap_or_else(|e| fail(e));
+
+    // the full breakdown at every depth up to the one asked for
+    if show_stats {
+        println!("{:>5} {:>14} {:>12} {:>8} {:>10} {:>10} {:>10} {:>9} {:>8} {:>8}",
+                 "depth", "nodes", "captures", "e.p.", "castles", "promotions",
+                 "checks", "discovery", "double", "mates");
+        for d in 1..=depth {
+            let s = pool.install(|| perft_stats(&game, d)).unwrap_or_else(|e| fail(e));
+            println!("{:>5} {:>14} {:>12} {:>8} {:>10} {:>10} {:>10} {:>9} {:>8} {:>8}",
+                     d, s.nodes, s.captures, s.en_passant, s.castles, s.promotions,
+                     s.checks, s.discovered_checks, s.double_checks, s.checkmates);
+        }
+        return;
+    }
+
+    let start = Instant::now();
+    let counts = pool.install(|| divide(&game, depth, table.as_ref())).unwrap_or_else(|e| fail(e));
+    if show_divide {
+        for (mv, n) in &counts {
+            println!("{}: {}", mv, n);
+        }
+        println!();
+    }
+    let nodes: u64 = counts.iter().map(|(_, n)| n).sum();
+    let elapsed = start.elapsed();
+
+    println!("Nodes searched: {}", nodes);
+    eprintln!("{} ms, {:.0} nps", elapsed.as_millis(), nodes as f64 / elapsed.as_secs_f64());
+}
+
+fn number(arg: Option<String>) -> usize {
+    arg.and_then(|n| n.parse().ok()).unwrap_or_else(|| usage())
+}
+
+fn fail(e: impl std::fmt::Display) -> ! {
+    eprintln!("{}", e);
+    process::exit(1);
+}
+
+fn usage() -> ! {
+    eprintln!("usage: perft --depth N [--fen FEN] [--divide] [--stats] [--threads N] [--hash MB]");
+    process::exit(2);
+}
@@ -0,0 +1,71 @@
+/* Tunes the evaluation weights on positions labelled with their game results
+ * and writes them as Rust, .pgn files are read as games and anything else
+ * as an EPD or FEN per line
+ * cargo run --profile perft --bin tune -- [FILE]... [--out PATH] [--k K]
+ */
+
+use std::{fs, process, time::Instant};
+use chess_wasm::engine::{
+    WEIGHTS,
+    tune::{decode_line, decode_pgn, to_rust, Tuner},
+};
+
+fn main() {
+    let mut paths = Vec::new();
+    let mut out = String::from("src/engine/weights.rs");
+    let mut k = None;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--out" => out = args.next().unwrap_or_else(|| usage()),
+            "--k" => k = Some(args.next().and_then(|k| k.parse::<f64>().ok()).unwrap_or_else(|| usage())),
+            _ => paths.push(arg),
+        }
+    }
+    if paths.is_empty() { usage(); }
+
+    let mut samples = Vec::new();
+    for path in &paths {
+        let text = fs::read_to_string(path).unwrap_or_else(|e| fail(format!("{}: {}", path, e)));
+        if path.ends_with(".pgn") {
+            let (games, errors) = decode_pgn(&text);
+            for (number, e) in errors {
+                eprintln!("{}: game {}: {}", path, number, e);
+            }
+            samples.extend(games);
+            continue;
+        }
+        for (i, line) in text.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') { continue; }
+            match decode_line(line) {
+                Ok(s) => samples.push(s),
+                Err(e) => eprintln!("{}:{}: {}", path, i + 1, e),
+            }
+        }
+    }
+
+    let start = Instant::now();
+    let tuner = Tuner::new(samples, &WEIGHTS);
+    if tuner.is_empty() { fail(String::from("no positions to tune on")); }
+    let k = k.unwrap_or_else(|| tuner.fit_k(&WEIGHTS));
+    eprintln!("{} quiet positions, k {:.3}, error {:.6}", tuner.len(), k, tuner.error(&WEIGHTS, k));
+
+    let tuned = tuner.tune(&WEIGHTS, k, |round, weights, error| {
+        eprintln!("round {} error {:.6} {:?} {:.1}s", round, error, weights.to_array(), start.elapsed().as_secs_f64());
+    });
+
+    let note = format!("{} positions, k {:.3}, error {:.6}", tuner.len(), k, tuner.error(&tuned, k));
+    fs::write(&out, to_rust(&tuned, &note)).unwrap_or_else(|e| fail(format!("{}: {}", out, e)));
+    eprintln!("wrote {}", out);
+}
+
+fn fail(message: String) -> ! {
+    eprintln!("{}", message);
+    process::exit(1);
+}
+
+fn usage() -> ! {
+    eprintln!("usage: tune [FILE]... [--out PATH] [--k K]");
+    process::exit(2);
+}
@@ -0,0 +1,251 @@
+/* Universal Chess Interface, for playing in a GUI or against other engines
+ * cargo run --profile perft --bin uci
+ */
+
+use std::{
+    fs,
+    io::{self, BufRead, Write},
+    sync::Arc,
+    thread::{self, JoinHandle},
+    time::{Duration, SystemTime},
+};
+use chess_wasm::{
+    engine::{
+        Engine,
+        EngineConfig,
+        MateLimits,
+        Network,
+        Ponder,
+        SearchLimits,
+        Skill,
+        TimeControl,
+        skill::{MAX_ELO, MAX_LEVEL, MIN_ELO},
+    },
+    game::{
+        Game,
+        fen,
+        notation::{from_uci, to_uci},
+        pieces::{Color, Piece},
+    },
+};
+
+const START: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+// set by the GUI with setoption, a limited strength plays at the rating
+struct Options {
+    skill: u8,
+    limit_strength: bool,
+    elo: u32,
+    multipv: usize,
+    config: EngineConfig,
+    network: Option<Arc<Network>>,
+}
+
+impl Options {
+    fn limits(&self) -> SearchLimits {
+        let skill = if self.limit_strength { Skill::from_elo(self.elo) } else { Skill::new(self.skill) };
+        let seed = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).map_or(0, |t| t.as_nanos() as u64);
+        SearchLimits { skill: Some(skill), multipv: self.multipv, seed, config: self.config, network: self.network.clone(), ..Default::default() }
+    }
+
+    // setoption name NAME value VALUE
+    fn set(&mut self, words: Vec<&str>) {
+        let value = words.iter().position(|w| *w == "value").unwrap_or(words.len());
+        let name = words.get(1..value).unwrap_or_default().join(" ").to_lowercase();
+        let value = words.get(value + 1..).unwrap_or_default().join(" ");
+        if name == "evalfile" {
+            // an empty path goes back to the hand written evaluation
+            self.network = None;
+            if value.is_empty() || value == "<empty>" { return; }
+            match fs::read(&value).map_err(|e| e.to_string()).and_then(|b| Network::from_bytes(&b).map_err(|e| e.to_string())) {
+                Ok(network) => self.network = Some(Arc::new(network)),
+                Err(e) => println!("info string {}: {}", value, e),
+            }
+            return;
+        }
+        match (name.as_str(), value.parse::<i32>().ok()) {
+            ("skill level", Some(v)) => self.skill = v.clamp(0, MAX_LEVEL as i32) as u8,
+            ("uci_elo", Some(v)) => self.elo = (v.max(0) as u32).clamp(MIN_ELO, MAX_ELO),
+            ("multipv", Some(v)) => self.multipv = v.max(1) as usize,
+            ("aggressiveness", Some(v)) => self.config.aggressiveness = v.clamp(-500, 500),
+            ("materialism", Some(v)) => self.config.materialism = v.clamp(0, 200),
+            ("contempt", Some(v)) => self.config.contempt = v.clamp(-500, 500),
+            ("simplification", Some(v)) => self.config.simplification = v.clamp(-500, 500),
+            ("uci_limitstrength", _) => self.limit_strength = value == "true",
+            ("ponder", _) => (), // the GUI decides when to ponder
+            _ => println!("info string unknown option {}", name),
+        }
+    }
+}
+
+// a search running in the background and its signals
+struct Running {
+    handle: JoinHandle<()>,
+    ponder: Arc<Ponder>,
+}
+
+impl Running {
+    // stopped searches still give their best move, as the GUI expects
+    fn stop(self) {
+        self.ponder.stop();
+        self.handle.join().ok();
+    }
+}
+
+fn main() {
+    let mut game = fen::decode(START).expect("start position");
+    let mut running: Option<Running> = None;
+    let mut options = Options {
+        skill: MAX_LEVEL,
+        limit_strength: false,
+        elo: MAX_ELO,
+        multipv: 1,
+        config: EngineConfig::default(),
+        network: None,
+    };
+
+    for line in io::stdin().lock().lines() {
+        let Ok(line) = line else { break };
+        let mut words = line.split_whitespace();
+        let command = words.next();
+
+        // only these leave a search running
+        if !matches!(command, Some("isready" | "ponderhit" | "uci") | None) {
+            if let Some(r) = running.take() { r.stop(); }
+        }
+        match command {
+            Some("uci") => {
+                println!("id name Chess.wasm");
+                println!("id author Chess.wasm contributors");
+                println!("option name Skill Level type spin default {} min 0 max {}", MAX_LEVEL, MAX_LEVEL);
+                println!("option name UCI_LimitStrength type check default false");
+                println!("option name UCI_Elo type spin default {} min {} max {}", MAX_ELO, MIN_ELO, MAX_ELO);
+                println!("option name MultiPV type spin default 1 min 1 max 256");
+                let config = EngineConfig::default();
+                // centipawns, scored to the nearest half pawn
+                println!("option name Aggressiveness type spin default {} min -500 max 500", config.aggressiveness);
+                println!("option name Materialism type spin default {} min 0 max 200", config.materialism);
+                println!("option name Contempt type spin default {} min -500 max 500", config.contempt);
+                println!("option name Simplification type spin default {} min -500 max 500", config.simplification);
+                println!("option name EvalFile type string default <empty>");
+                println!("option name Ponder type check default false");
+                println!("uciok");
+            },
+            Some("isready") => println!("readyok"),
+            Some("ucinewgame") => game = fen::decode(START).expect("start position"),
+            Some("position") => match position(words.collect()) {
+                Ok(g) => game = g,
+                Err(e) => println!("info string {}", e),
+            },
+            Some("setoption") => options.set(words.collect()),
+            Some("go") => running = Some(go(&game, options.limits(), words.collect())),
+            Some("ponderhit") => if let Some(r) = &running { r.ponder.hit() },
+            Some("stop") => (), // stopped above
+            Some("quit") => break,
+            _ => (),
+        }
+        io::stdout().flush().ok();
+    }
+
+    // input ended without a quit, the last search still gets its move out
+    if let Some(r) = running { r.handle.join().ok(); }
+}
+
+// position [startpos | fen FEN] [moves MOVE...]
+fn position(words: Vec<&str>) -> Result<Game, String> {
+    let moves = words.iter().position(|w| *w == "moves").unwrap_or(words.len());
+    let mut game = match words.first() {
+        Some(&"startpos") => fen::decode(START),
+        Some(&"fen") => fen::decode(&words[1..moves].join(" ")),
+        _ => return Err(String::from("expected startpos or fen")),
+    }.map_err(|e| e.to_string())?;
+
+    for uci in words.iter().skip(moves + 1) {
+        let mv = from_uci(&game, uci).map_err(|e| e.to_string())?;
+        game.move_piece(*mv.bits());
+    }
+    Ok(game)
+}
+
+/* go [ponder] [depth N] [movetime MS] [mate N] [wtime MS] [btime MS] [winc MS]
+ * [binc MS] [movestogo N] [infinite], searched in the background until done or
+ * stopped. Only the side to move's clock is kept, a clock without a time is
+ * left alone. Pondering and infinite searches give no move until told to
+ */
+fn go(game: &Game, mut limits: SearchLimits, words: Vec<&str>) -> Running {
+    let ponder = Arc::new(Ponder::new(words.iter().any(|w| matches!(*w, "ponder" | "infinite"))));
+    limits.ponder = Some(ponder.clone());
+    let mut mate = None;
+    let mut clock = TimeControl::default();
+    let mut timed = false;
+    let side = if game.turn == Color::White { "w" } else { "b" };
+
+    let mut words = words.into_iter();
+    while let Some(word) = words.next() {
+        let value = words.clone().next().and_then(|v| v.parse::<i64>().ok()).map(|v| v.max(0) as u64);
+        match (word, value) {
+            ("depth", Some(d)) => limits.depth = Some(d.min(u8::MAX as u64) as u8),
+            ("movetime", Some(ms)) => limits.movetime = Some(Duration::from_millis(ms)),
+            ("mate", Some(n)) => mate = Some(n.min(u8::MAX as u64) as u8),
+            ("wtime" | "btime", Some(ms)) if word.starts_with(side) => (clock.time, timed) = (Duration::from_millis(ms), true),
+            ("winc" | "binc", Some(ms)) if word.starts_with(side) => clock.increment = Duration::from_millis(ms),
+            ("movestogo", Some(n)) if n > 0 => clock.moves_to_go = Some(n.min(u32::MAX as u64) as u32),
+            _ => continue,
+        }
+        words.next();
+    }
+    if timed {
+        limits.time = Some(clock);
+    }
+
+    let game = game.clone();
+    let handle = thread::spawn(move || search(&game, &limits, mate));
+    Running { handle, ponder }
+}
+
+fn search(game: &Game, limits: &SearchLimits, mate: Option<u8>) {
+    // a proven mate is played, without one the usual search is
+    if let Some(moves) = mate {
+        let mate = MateLimits { moves, movetime: limits.movetime, stop: limits.ponder.as_ref().map(|p| p.stop_flag()), ..Default::default() };
+        match Engine::find_mate(game, &mate) {
+            Ok(Some(tree)) => {
+                let mut line = game.clone();
+                let pv = tree.line().iter().map(|mv| {
+                    let uci = to_uci(&line, mv).unwrap_or_default();
+                    line.move_piece(*mv.bits());
+                    uci
+                }).collect::<Vec<_>>();
+                println!("info score mate {} pv {}", tree.moves, pv.join(" "));
+                println!("bestmove {}", pv[0]);
+                return;
+            },
+            Ok(None) => println!("info string no mate in {}", moves),
+            Err(e) => println!("info string {}", e),
+        }
+    }
+
+    match Engine::analyse(game, limits) {
+        Ok(result) => {
+            let best = to_uci(game, &result.best_move).unwrap_or_default();
+            let mut after = game.clone();
+            after.move_piece(*result.best_move.bits());
+            let ponder = result.ponder.and_then(|mv| to_uci(&after, &mv).ok());
+
+            for (k, (mv, score)) in result.lines.iter().enumerate() {
+                let mut pv = to_uci(game, mv).unwrap_or_default();
+                if let Some(reply) = ponder.as_ref().filter(|_| pv == best) {
+                    pv = format!("{} {}", pv, reply);
+                }
+                println!("info depth {} multipv {} score {} nodes {} pv {}", result.depth, k + 1, score, result.nodes, pv);
+            }
+            match ponder {
+                Some(reply) => println!("bestmove {} ponder {}", best, reply),
+                None => println!("bestmove {}", best),
+            }
+        },
+        Err(e) => {
+            println!("info string {}", e);
+            println!("bestmove 0000");
+        },
+    }
+}
@@ -1,52 +1,227 @@
+use std::{
+    error::Error,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 use wasm_bindgen::prelude::*;
-use js_sys;
 
-use crate::{game::{
+use crate::{error::ErrorCode, game::{
     util::{*, promote},
     Game,
+    board::Bits,
+    MoveError,
     pieces::Color,
     pieces::Piece,
     pieces::Pieces,
     fen,
     notation::{
         algebraic_to_bits,
-        bits_to_algebraic
+        bits_to_algebraic,
+        to_uci,
     }
-}, engine::Engine};
+}, engine::{
+    centipawns, evaluate_traced, Engine, EngineConfig, MateLimits, MateTree, Network, Score, SearchLimits, SearchResult,
+    Skill, TimeControl, Trace, TranspositionTable, WEIGHTS,
+    evaluate::{evaluate_traced_in, GameState},
+}};
+
+/* Errors are thrown as JS Error objects with the
+ * variant's identifier attached as `code`
+ */
+fn js_error<E: Error + ErrorCode>(e: E) -> JsValue {
+    let error = js_sys::Error::new(&e.to_string());
+    js_sys::Reflect::set(&error, &"code".into(), &e.code().into()).ok();
+    error.into()
+}
+
+fn object_error(field: &str) -> JsValue {
+    let error = js_sys::Error::new(&format!("Wasm object access error ({})", field));
+    js_sys::Reflect::set(&error, &"code".into(), &"WASM_OBJECT_ACCESS".into()).ok();
+    error.into()
+}
+
+#[wasm_bindgen]
+pub fn validate(fen: &str) -> Result<(), JsValue> {
+    fen::validate(fen).map_err(js_error)
+}
 
+/* Every reason the position is illegal, as an array
+ * of errors with codes, empty when the position is legal
+ */
 #[wasm_bindgen]
-pub fn validate(fen: &str) -> Result<(), JsError> {
-    match fen::validate(fen) {
-        Ok(_) => Ok(()),
-        Err(e) => return Err(JsError::new(&format!("{}", e)))
+pub fn validate_strict(fen: &str) -> js_sys::Array {
+    let arr = js_sys::Array::new();
+    if let Err(errors) = fen::validate_strict(fen) {
+        for e in errors {
+            arr.push(&js_error(e));
+        }
     }
+    arr
+}
+
+// Chess960 starting position by index, 518 is standard chess
+#[wasm_bindgen]
+pub fn chess960(index: u16) -> Result<String, JsValue> {
+    fen::chess960(index).map_err(js_error)
+}
+
+// starts the rayon pool the search shares out to, 'await initThreadPool(n)'
+#[cfg(all(target_arch = "wasm32", feature = "parallel"))]
+pub use wasm_bindgen_rayon::init_thread_pool;
+
+// the network loaded for the engine, none until one is fetched
+static NETWORK: Mutex<Option<Arc<Network>>> = Mutex::new(None);
+
+/* Loads a network file's bytes, the engine evaluates with it
+ * from then on in place of the hand written evaluation
+ */
+#[wasm_bindgen]
+pub fn load_network(bytes: &[u8]) -> Result<(), JsValue> {
+    let network = Network::from_bytes(bytes).map_err(js_error)?;
+    *NETWORK.lock().map_err(|_| object_error("network"))? = Some(Arc::new(network));
+    Ok(())
 }
 
 #[wasm_bindgen]
-pub fn best_move(fen: &str) -> Result<js_sys::Object, JsError> {
-    let game: Game = match fen::decode(fen) {
-        Ok(g) => g,
-        Err(e) => return Err(JsError::new(&format!("{}", e)))
+pub fn unload_network() {
+    if let Ok(mut network) = NETWORK.lock() { *network = None; }
+}
+
+/* Options are optional, '{ skill, elo }' weakens the engine
+ * to a level from 0 to 20 or roughly to a rating, the
+ * level wins when both are given. 'config' is an engine
+ * config as an object or a JSON string, 'nnue: false'
+ * leaves a loaded network unused. 'clock' is the side to
+ * move's '{ time, increment, delay, movestogo }' in
+ * milliseconds, left out for sudden death and no bonus
+ */
+fn search_limits(options: Option<js_sys::Object>) -> Result<SearchLimits, JsValue> {
+    let value = |key: &str| options.as_ref().and_then(|o| js_sys::Reflect::get(o, &key.into()).ok());
+    let get = |key: &str| value(key).and_then(|v| v.as_f64());
+
+    let skill = match (get("skill"), get("elo")) {
+        (Some(level), _) => Some(Skill::new(level as u8)),
+        (None, Some(elo)) => Some(Skill::from_elo(elo as u32)),
+        (None, None) => None,
+    };
+    let config = match value("config") {
+        Some(v) if v.is_string() => EngineConfig::from_json(&v.as_string().unwrap_or_default()).map_err(js_error)?,
+        Some(v) if v.is_object() => {
+            let json = js_sys::JSON::stringify(&v)?;
+            EngineConfig::from_json(&String::from(json)).map_err(js_error)?
+        },
+        _ => EngineConfig::default(),
     };
 
-    let mut best_move = Engine::best_move(fen.into())
-        .map_err(|e| JsError::new(&format!("{}", e)))?;
+    // a weaker engine should not play the same game every time
+    let seed = (js_sys::Math::random() * u32::MAX as f64) as u64;
+    let clock = value("clock").filter(|c| c.is_object());
+    let field = |key: &str| clock.as_ref().and_then(|c| js_sys::Reflect::get(c, &key.into()).ok()).and_then(|v| v.as_f64());
+    let ms = |key: &str| Duration::from_millis(field(key).unwrap_or(0.0).max(0.0) as u64);
+    let time = clock.as_ref().map(|_| TimeControl {
+        time: ms("time"),
+        increment: ms("increment"),
+        delay: ms("delay"),
+        moves_to_go: field("movestogo").filter(|n| *n >= 1.0).map(|n| n as u32),
+    });
+
+    let network = match value("nnue").and_then(|v| v.as_bool()) {
+        Some(false) => None,
+        _ => NETWORK.lock().ok().and_then(|n| n.clone()),
+    };
 
-    let mut current = 0u128;
-    for p in &game.pieces {
-        if p.color() == &game.turn { current |= p.bits(); }
-    }
+    Ok(SearchLimits { time, skill, seed, config, network, ..Default::default() })
+}
+
+// the config as JSON with every field, missing ones filled with defaults
+#[wasm_bindgen]
+pub fn engine_config(json: &str) -> Result<String, JsValue> {
+    Ok(EngineConfig::from_json(json).map_err(js_error)?.to_json())
+}
+
+#[wasm_bindgen]
+pub fn best_move(fen: &str, options: Option<js_sys::Object>) -> Result<js_sys::Object, JsValue> {
+    let game: Game = fen::decode(fen).map_err(js_error)?;
+    let result = Engine::analyse(&game, &search_limits(options)?).map_err(js_error)?;
+    move_object(&game, &result)
+}
+
+/* The position pondered on while the opponent thinks, the table
+ * keeps what each slice found for the next and for the hit
+ */
+struct PonderSession {
+    game: Game,
+    table: Arc<TranspositionTable>,
+    depth: u8,
+}
+
+static PONDER: Mutex<Option<PonderSession>> = Mutex::new(None);
+
+// megabytes of the table kept between slices
+const PONDER_HASH: usize = 16;
+
+/* A search blocks the worker it runs in, so pondering goes in
+ * slices the worker calls between its messages. Start with the
+ * position after best_move's move and its 'ponder' reply, step
+ * until the opponent moves, then hit or stop
+ */
+#[wasm_bindgen]
+pub fn ponder_start(fen: &str) -> Result<(), JsValue> {
+    let game: Game = fen::decode(fen).map_err(js_error)?;
+    let table = Arc::new(TranspositionTable::new(PONDER_HASH));
+    *PONDER.lock().map_err(|_| object_error("ponder"))? = Some(PonderSession { game, table, depth: 0 });
+    Ok(())
+}
+
+// searches a ply deeper for at most the milliseconds, returns the depth pondered
+#[wasm_bindgen]
+pub fn ponder_step(ms: u32) -> Result<u8, JsValue> {
+    let mut session = PONDER.lock().map_err(|_| object_error("ponder"))?;
+    let Some(session) = session.as_mut() else { return Ok(0) };
+    let limits = SearchLimits {
+        depth: Some(session.depth.saturating_add(1)),
+        movetime: Some(Duration::from_millis(ms as u64)),
+        table: Some(session.table.clone()),
+        ..Default::default()
+    };
+    let result = Engine::analyse(&session.game, &limits).map_err(js_error)?;
+    session.depth = session.depth.max(result.depth);
+    Ok(session.depth)
+}
+
+/* The reply was played, the real search starts on the clock with
+ * what pondering found in the table, the options are best_move's
+ */
+#[wasm_bindgen]
+pub fn ponder_hit(options: Option<js_sys::Object>) -> Result<js_sys::Object, JsValue> {
+    let session = PONDER.lock().map_err(|_| object_error("ponder"))?.take()
+        .ok_or_else(|| object_error("ponder"))?;
+    let limits = SearchLimits { table: Some(session.table), ..search_limits(options)? };
+    let result = Engine::analyse(&session.game, &limits).map_err(js_error)?;
+    move_object(&session.game, &result)
+}
+
+// another reply was played, what was pondered is thrown away
+#[wasm_bindgen]
+pub fn ponder_stop() {
+    if let Ok(mut session) = PONDER.lock() { *session = None; }
+}
+
+fn move_object(game: &Game, result: &SearchResult) -> Result<js_sys::Object, JsValue> {
+    let best_move = &result.best_move;
+
+    let current = game.board.color(game.turn);
 
-    match *best_move.bits() {
-        castle::K_ZONE => { best_move.set_bits(&castle::K_MOVE); }
-        castle::Q_ZONE => { best_move.set_bits(&castle::Q_MOVE); }
-        castle::k_ZONE => { best_move.set_bits(&castle::k_MOVE); }
-        castle::q_ZONE => { best_move.set_bits(&castle::q_MOVE); }
-        _ => ()
-    }
     let mut src = current & best_move.bits();  // find the matching starting location
     let mut dst = best_move.bits() & !src; // subtract starting pos from move map
-    let mut promotion = 0u128;
+
+    // castles are shown as the king moving two squares in
+    // standard chess and as the king taking its rook in Chess960
+    if let Some(c) = castle::castle_move(game, best_move.bits()) {
+        src = c.king_from;
+        dst = if game.chess960 { c.rook_from } else { c.king_to };
+    }
+    let mut promotion = 0u64;
     if let Pieces::Pawn(p) = best_move {
         match p.color() {
             Color::White => {
@@ -75,43 +250,167 @@ pub fn best_move(fen: &str) -> Result<js_sys::Object, JsError> {
     // Convert bits to string
     let obj = js_sys::Object::new();
 
-    let (from ,to) = (bits_to_algebraic(&src)?, bits_to_algebraic(&dst)?);
+    let from = bits_to_algebraic(&src).map_err(js_error)?;
+    let to = bits_to_algebraic(&dst).map_err(js_error)?;
 
     // Wrap in JS object
     js_sys::Reflect::set(&obj, &"from".into(), &JsValue::from_str(&from))
-        .map_err(|_| JsError::new(&format!("Wasm object access error (from): {}", src)))?;
+        .map_err(|_| object_error("from"))?;
     js_sys::Reflect::set(&obj, &"to".into(), &JsValue::from_str(&to))
-        .map_err(|_| JsError::new(&format!("Wasm object access error (to): {}", dst)))?;
+        .map_err(|_| object_error("to"))?;
     js_sys::Reflect::set(&obj, &"promotion".into(), &JsValue::from_str(&promotion))
-        .map_err(|_| JsError::new(&format!("Wasm object access error (promotion): {}", promotion)))?;
+        .map_err(|_| object_error("promotion"))?;
+
+    // centipawns for the side to move, or moves to mate with
+    // a negative count when the side to move is being mated
+    let (score, mate) = match result.score {
+        Score::Centipawns(cp) => (JsValue::from(cp), JsValue::NULL),
+        Score::Mate(n) => (JsValue::NULL, JsValue::from(n)),
+    };
+    js_sys::Reflect::set(&obj, &"score".into(), &score)
+        .map_err(|_| object_error("score"))?;
+    js_sys::Reflect::set(&obj, &"mate".into(), &mate)
+        .map_err(|_| object_error("mate"))?;
+
+    // the reply to ponder on in UCI, or null
+    let mut after = game.clone();
+    after.move_piece(*best_move.bits());
+    let ponder = match &result.ponder {
+        Some(reply) => JsValue::from_str(&to_uci(&after, reply).map_err(js_error)?),
+        None => JsValue::NULL,
+    };
+    js_sys::Reflect::set(&obj, &"ponder".into(), &ponder)
+        .map_err(|_| object_error("ponder"))?;
 
     Ok(obj)
 }
 
+/* Why the evaluation thinks what it does, '{ phase, score, mate, terms,
+ * phases }' where terms is '[{ name, group, white, black }]' with each
+ * side's share in centipawns, positive is good for that side. Group is
+ * one of material, piece_square, pawn_structure, king_safety, mobility
+ * and endgame, the knowledge the term belongs to. Score is the terms
+ * summed for the side to move, the pawn structure's quarter pawns rounded
+ * to the evaluation's half pawn, and mate 0 when it is checkmated. Phases
+ * holds '{ score, mate, terms }' for each of opening, middlegame and
+ * endgame as though the game were in it. The options are best_move's,
+ * only 'config' counts. This is the hand written evaluation, a loaded
+ * network is not broken down
+ */
 #[wasm_bindgen]
-pub fn moves(fen: &str) -> Result<js_sys::Array, JsError> {
-    let game: Game = match fen::decode(fen) {
-        Ok(g) => g,
-        Err(e) => return Err(JsError::new(&format!("{}", e)))
+pub fn evaluate(fen: &str, options: Option<js_sys::Object>) -> Result<js_sys::Object, JsValue> {
+    let game: Game = fen::decode(fen).map_err(js_error)?;
+    let (_, info) = game.moves_verbose().map_err(js_error)?;
+    let config = search_limits(options)?.config;
+
+    let trace = evaluate_traced(&game, &info, &config);
+    let obj = trace_object(&trace)?;
+    js_sys::Reflect::set(&obj, &"phase".into(), &JsValue::from_str(trace.state.name()))
+        .map_err(|_| object_error("phase"))?;
+
+    let phases = js_sys::Object::new();
+    for state in GameState::ALL {
+        let trace = evaluate_traced_in(&game, &info, &config, &WEIGHTS, state);
+        js_sys::Reflect::set(&phases, &state.name().into(), &trace_object(&trace)?.into())
+            .map_err(|_| object_error("phases"))?;
+    }
+    js_sys::Reflect::set(&obj, &"phases".into(), &phases)
+        .map_err(|_| object_error("phases"))?;
+    Ok(obj)
+}
+
+fn trace_object(trace: &Trace) -> Result<js_sys::Object, JsValue> {
+    let obj = js_sys::Object::new();
+
+    let (score, mate) = match trace.checkmate {
+        true => (JsValue::NULL, JsValue::from(0)),
+        false => (JsValue::from(centipawns(trace.score())), JsValue::NULL),
     };
-    let mut current = 0u128;
-    for p in &game.pieces {
-        if p.color() == &game.turn { current |= p.bits(); }
+    js_sys::Reflect::set(&obj, &"score".into(), &score)
+        .map_err(|_| object_error("score"))?;
+    js_sys::Reflect::set(&obj, &"mate".into(), &mate)
+        .map_err(|_| object_error("mate"))?;
+
+    let terms = js_sys::Array::new();
+    for ((name, group), term) in Trace::NAMES.iter().zip(Trace::GROUPS).zip(trace.centipawns()) {
+        let t = js_sys::Object::new();
+        js_sys::Reflect::set(&t, &"name".into(), &JsValue::from_str(name))
+            .map_err(|_| object_error("name"))?;
+        js_sys::Reflect::set(&t, &"group".into(), &JsValue::from_str(group))
+            .map_err(|_| object_error("group"))?;
+        js_sys::Reflect::set(&t, &"white".into(), &JsValue::from(term.white))
+            .map_err(|_| object_error("white"))?;
+        js_sys::Reflect::set(&t, &"black".into(), &JsValue::from(term.black))
+            .map_err(|_| object_error("black"))?;
+        terms.push(&t);
+    }
+    js_sys::Reflect::set(&obj, &"terms".into(), &terms)
+        .map_err(|_| object_error("terms"))?;
+    Ok(obj)
+}
+
+// longest a mate search may block the page without a movetime
+const MATE_MOVETIME: u32 = 10_000;
+
+/* The shortest forced mate within the given moves, or null
+ * when there is none. Each node is '{ move, mate, defences }'
+ * with moves in UCI and 'mate' the moves left to mate, every
+ * defence is '{ move, reply }' where the reply is the next node.
+ * Throws ENGINE_OUT_OF_TIME after movetime milliseconds
+ */
+#[wasm_bindgen]
+pub fn find_mate(fen: &str, moves: u8, checks_only: bool, movetime: Option<u32>) -> Result<JsValue, JsValue> {
+    let game: Game = fen::decode(fen).map_err(js_error)?;
+    let movetime = Some(Duration::from_millis(movetime.unwrap_or(MATE_MOVETIME) as u64));
+    let limits = MateLimits { moves, checks_only, movetime, ..Default::default() };
+    match Engine::find_mate(&game, &limits).map_err(js_error)? {
+        Some(tree) => Ok(mate_tree(&game, &tree)?.into()),
+        None => Ok(JsValue::NULL),
     }
+}
+
+fn mate_tree(game: &Game, tree: &MateTree) -> Result<js_sys::Object, JsValue> {
+    let obj = js_sys::Object::new();
+    let mv = to_uci(game, &tree.mv).map_err(js_error)?;
+    js_sys::Reflect::set(&obj, &"move".into(), &JsValue::from_str(&mv))
+        .map_err(|_| object_error("move"))?;
+    js_sys::Reflect::set(&obj, &"mate".into(), &JsValue::from(tree.moves))
+        .map_err(|_| object_error("mate"))?;
+
+    let mut next = game.clone();
+    next.move_piece(*tree.mv.bits());
+    let defences = js_sys::Array::new();
+    for (defence, reply) in &tree.defences {
+        let d = js_sys::Object::new();
+        let mut after = next.clone();
+        after.move_piece(*defence.bits());
+        let defence = to_uci(&next, defence).map_err(js_error)?;
+        js_sys::Reflect::set(&d, &"move".into(), &JsValue::from_str(&defence))
+            .map_err(|_| object_error("move"))?;
+        js_sys::Reflect::set(&d, &"reply".into(), &mate_tree(&after, reply)?.into())
+            .map_err(|_| object_error("reply"))?;
+        defences.push(&d);
+    }
+    js_sys::Reflect::set(&obj, &"defences".into(), &defences)
+        .map_err(|_| object_error("defences"))?;
+    Ok(obj)
+}
+
+#[wasm_bindgen]
+pub fn moves(fen: &str) -> Result<js_sys::Array, JsValue> {
+    let game: Game = fen::decode(fen).map_err(js_error)?;
+    let current = game.board.color(game.turn);
 
     let arr = js_sys::Array::new();
 
-    for mut m in game.moves().unwrap() {
-        match *m.bits() {
-            castle::K_ZONE => { m.set_bits(&castle::K_MOVE); }
-            castle::Q_ZONE => { m.set_bits(&castle::Q_MOVE); }
-            castle::k_ZONE => { m.set_bits(&castle::k_MOVE); }
-            castle::q_ZONE => { m.set_bits(&castle::q_MOVE); }
-            _ => ()
-        }
+    for m in game.moves().map_err(js_error)? {
         let mut src = current & m.bits();  // find the matching starting location
         let mut dst = m.bits() & !src; // subtract starting pos from move map
-        let mut promotion = 0u128;
+        if let Some(c) = castle::castle_move(&game, m.bits()) {
+            src = c.king_from;
+            dst = if game.chess960 { c.rook_from } else { c.king_to };
+        }
+        let mut promotion = 0u64;
         if let Pieces::Pawn(p) = m {
             match p.color() {
                 Color::White => {
@@ -139,15 +438,16 @@ pub fn moves(fen: &str) -> Result<js_sys::Array, JsError> {
         // Convert bits to string
         let obj = js_sys::Object::new();
 
-        let (from ,to) = (bits_to_algebraic(&src)?, bits_to_algebraic(&dst)?);
+        let from = bits_to_algebraic(&src).map_err(js_error)?;
+        let to = bits_to_algebraic(&dst).map_err(js_error)?;
 
         // Wrap in JS object
         js_sys::Reflect::set(&obj, &"from".into(), &JsValue::from_str(&from))
-            .map_err(|_| JsError::new("Wasm object access error (from)"))?;
+            .map_err(|_| object_error("from"))?;
         js_sys::Reflect::set(&obj, &"to".into(), &JsValue::from_str(&to))
-            .map_err(|_| JsError::new("Wasm object access error (to)"))?;
+            .map_err(|_| object_error("to"))?;
         js_sys::Reflect::set(&obj, &"promotion".into(), &JsValue::from_str(&promotion))
-            .map_err(|_| JsError::new("Wasm object access error (promotion)"))?;
+            .map_err(|_| object_error("promotion"))?;
 
         arr.push(&obj);
     }
@@ -155,49 +455,58 @@ pub fn moves(fen: &str) -> Result<js_sys::Array, JsError> {
     Ok(arr)
 }
 
+// square names of a bit board, for highlighting
+fn squares(bits: u64) -> Result<js_sys::Array, JsValue> {
+    let arr = js_sys::Array::new();
+    for bit in Bits(bits) {
+        arr.push(&JsValue::from_str(&bits_to_algebraic(&bit).map_err(js_error)?));
+    }
+    Ok(arr)
+}
+
+// squares of the pieces giving check to the side to move
 #[wasm_bindgen]
-pub fn move_piece(fen: &str, obj: js_sys::Object) -> Result<String, JsError> {
-    let mut game: Game = match fen::decode(fen) {
-        Ok(g) => g,
-        Err(e) => return Err(JsError::new(&format!("{}", e)))
-    };
+pub fn checkers(fen: &str) -> Result<js_sys::Array, JsValue> {
+    let game: Game = fen::decode(fen).map_err(js_error)?;
+    squares(game.checkers().map_err(js_error)?)
+}
+
+// squares of the side to move's pieces pinned to its king
+#[wasm_bindgen]
+pub fn pinned(fen: &str) -> Result<js_sys::Array, JsValue> {
+    let game: Game = fen::decode(fen).map_err(js_error)?;
+    squares(game.pinned().map_err(js_error)?)
+}
+
+#[wasm_bindgen]
+pub fn move_piece(fen: &str, obj: js_sys::Object) -> Result<String, JsValue> {
+    let mut game: Game = fen::decode(fen).map_err(js_error)?;
 
     // Unwrap JS object to rust data-type
     let from = js_sys::Reflect::get(&obj, &"from".into())
-        .map_err(|_| JsError::new("Wasm object access error (from)"))?;
+        .map_err(|_| object_error("from"))?;
 
     let to = js_sys::Reflect::get(&obj, &"to".into())
-        .map_err(|_| JsError::new("Wasm object access error (to)"))?;
+        .map_err(|_| object_error("to"))?;
 
     let promotion = js_sys::Reflect::get(&obj, &"promotion".into())
-        .map_err(|_| JsError::new("Wasm object access error (promotion)"))?;
+        .map_err(|_| object_error("promotion"))?;
 
     let src = algebraic_to_bits(JsValue::as_string(&from)
-                                .ok_or_else(|| JsError::new("Move parse error (src)"))?)?;
+                                .ok_or_else(|| object_error("from"))?).map_err(js_error)?;
 
     let dst = algebraic_to_bits(JsValue::as_string(&to)
-                                .ok_or_else(|| JsError::new("Move parse error (dst)"))?)?;
+                                .ok_or_else(|| object_error("to"))?).map_err(js_error)?;
 
     let promotion = JsValue::as_string(&promotion)
-        .ok_or_else(|| JsError::new("Move parse error (promotion)"))?;
+        .ok_or_else(|| object_error("promotion"))?;
 
     let mut mv = src | dst;
 
-    // Check castle move
-    match mv {
-        castle::K_MOVE => {
-            if game.castling & castle::K_ID != 0 { mv = castle::K_ZONE; }
-        },
-        castle::Q_MOVE => {
-            if game.castling & castle::Q_ID != 0 { mv = castle::Q_ZONE; }
-        },
-        castle::k_MOVE => {
-            if game.castling & castle::k_ID != 0 { mv = castle::k_ZONE; }
-        },
-        castle::q_MOVE => {
-            if game.castling & castle::q_ID != 0 { mv = castle::q_ZONE; }
-        },
-        _ => (),
+    // Check castle move, the king two squares over in standard
+    // chess, the king taking its own rook is accepted in both
+    if let Some(castle) = castle::from_king_move(&game, &mv) {
+        mv = castle;
     }
 
     // Check pawn promotion
@@ -209,22 +518,17 @@ pub fn move_piece(fen: &str, obj: js_sys::Object) -> Result<String, JsError> {
         "n" => mv |= promote::BLACK_KNIGHT,
         "b" => mv |= promote::BLACK_BISHOP,
         "" => (),
-        _ => { return Err(JsError::new("Invalid promotion")); }
+        _ => { return Err(js_error(MoveError::BadPromotion { piece: promotion })); }
     }
 
     // Validate move
-    if let Err(e) = game.valid_move(&(mv)) {
-        return Err(JsError::new(&format!("{}", e)));
-    }
+    game.valid_move(&mv).map_err(js_error)?;
 
     // Execute move
     game.move_piece(mv);
 
     // Return new fen
-    let return_fen: String = match fen::encode(&game) {
-        Ok(f) => f,
-        Err(e) => return Err(JsError::new(&format!("{}", e)))
-    };
+    let return_fen: String = fen::encode(&game).map_err(js_error)?;
 
     Ok(return_fen)
 }
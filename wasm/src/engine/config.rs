@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+
+use super::EngineError;
+
+/* How the engine likes to play, every field in centipawns and
+ * rounded to the evaluation's half pawn where it is scored.
+ * Aggressiveness is added for each piece attacking the squares
+ * around the enemy king. Materialism is what a pawn's worth of
+ * material counts for, 100 as usual. Contempt is what a draw
+ * costs the engine, the draws the search knows being stalemate
+ * and the fifty move rule as repetitions are not detected.
+ * Simplification is the most earned for trading down when ahead,
+ * reached when the opponent has nothing but its king left, on top
+ * of the flat bonus for being ahead before the endgame
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EngineConfig {
+    pub aggressiveness: i32,
+    pub materialism: i32,
+    pub contempt: i32,
+    pub simplification: i32,
+}
+
+impl Default for EngineConfig {
+    fn default() -> Self {
+        EngineConfig {
+            aggressiveness: 0,
+            materialism: 100,
+            contempt: 0,
+            simplification: 0,
+        }
+    }
+}
+
+impl EngineConfig {
+    // missing fields keep their defaults
+    pub fn from_json(json: &str) -> Result<Self, EngineError> {
+        serde_json::from_str(json).map_err(|e| EngineError::Config(e.to_string()))
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
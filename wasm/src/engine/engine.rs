@@ -1,33 +1,278 @@
-use super::{EngineError, minimax};
-use std::{error::Error, sync::{Arc, Mutex}};
+use super::{
+    EngineError,
+    Search,
+    INFINITY,
+    EngineConfig,
+    Score,
+    skill::{Skill, MAX_LEVEL},
+    time::{TimeControl, TimeManager},
+    mate::{MateLimits, MateSearch, MateTree},
+    nnue::Network,
+    ponder::Ponder,
+    evaluate::PAWN_VAL,
+    negamax::{pvs, Selectivity},
+    tt::{Bound, Entry, TranspositionTable},
+};
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicU64, Ordering::Relaxed},
+    },
+    time::Duration,
+};
+use web_time::Instant;
 use crate::game::{
+    Game,
     pieces::{
         Piece,
         Pieces,
     },
     fen
 };
-use rayon::prelude::*;
 
 pub struct Engine();
 
+// size of the transposition table shared by the search threads
+const HASH_MEGABYTES: usize = 16;
+
+// first aspiration window either side of the last score, a pawn is 2
+const ASPIRATION: i32 = 2;
+
+// deepest a clock lets the iterative deepening go
+const MAX_DEPTH: u8 = 64;
+
+// a capture this far ahead of every other move by this depth is played early
+const EASY_MARGIN: i32 = 2 * PAWN_VAL;
+const EASY_DEPTH: u8 = 4;
+
+/* Limits placed on a search, when depth is None
+ * the depth is picked from the material on the board,
+ * or by the time manager when there is a clock to play
+ * on. A movetime stops the iterative deepening, keeping
+ * the best move of the last completed depth, as does running
+ * past the nodes, though the first depth always completes.
+ * Threads default to the size of the rayon pool, one
+ * thread always gives the same move for the same
+ * position and seed. Multipv scores that many of the best
+ * moves exactly instead of only the best, a skill below
+ * the top weakens the search and the seed varies how.
+ * The config sets the engine's style of play, a network
+ * evaluates in place of the hand written evaluation.
+ * A ponder lets the search run in the background, a table
+ * carries what one search learnt over to the next
+ */
+#[derive(Clone, Default)]
+pub struct SearchLimits {
+    pub depth: Option<u8>,
+    pub movetime: Option<Duration>,
+    pub time: Option<TimeControl>,
+    pub nodes: Option<u64>,
+    pub threads: Option<usize>,
+    pub multipv: usize,
+    pub skill: Option<Skill>,
+    pub seed: u64,
+    pub selectivity: Selectivity,
+    pub config: EngineConfig,
+    pub network: Option<Arc<Network>>,
+    pub ponder: Option<Arc<Ponder>>,
+    pub table: Option<Arc<TranspositionTable>>,
+}
+
+/* The move played from the last completed depth, depth in plies.
+ * Lines are the best moves found with their scores, best first
+ * and as many as multipv asked for, a weaker skill does not
+ * always play the first. Ponder is the reply expected to it
+ */
+#[derive(Clone)]
+pub struct SearchResult {
+    pub best_move: Pieces,
+    pub score: Score,
+    pub depth: u8,
+    pub nodes: u64,
+    pub lines: Vec<(Pieces, Score)>,
+    pub ponder: Option<Pieces>,
+}
+
 impl Engine {
-    pub fn best_move(fen: String) -> Result<Pieces, Box<dyn Error>> {
+    pub fn best_move(fen: String) -> Result<Pieces, EngineError> {
         let game = fen::decode(&fen)?;
+        Engine::search(&game, &SearchLimits::default())
+    }
+
+    pub fn search(game: &Game, limits: &SearchLimits) -> Result<Pieces, EngineError> {
+        Ok(Engine::analyse(game, limits)?.best_move)
+    }
+
+    pub fn analyse(game: &Game, limits: &SearchLimits) -> Result<SearchResult, EngineError> {
         let (moves, info) = game.moves_verbose()?;
         if info.checkmate {
-            return Err(Box::new(EngineError("Checkmate".into())));
+            return Err(EngineError::Checkmate);
         }
         if info.stalemate {
-            return Err(Box::new(EngineError("Draw".into())));
+            return Err(EngineError::Stalemate);
+        }
+
+        let skill = limits.skill.unwrap_or(Skill::new(MAX_LEVEL));
+        let mut manager = limits.time.as_ref().map(TimeManager::new);
+        if let Some(manager) = manager.as_mut().filter(|_| moves.len() == 1) {
+            manager.single_reply();
         }
+        let pondering = limits.ponder.as_ref().is_some_and(|p| p.is_pondering());
+        let depth = limits.depth.unwrap_or_else(|| if manager.is_some() || pondering { MAX_DEPTH } else { Engine::depth(game) })
+            .min(skill.depth().unwrap_or(u8::MAX))
+            .max(1);
+        let max_nodes = match (limits.nodes, skill.nodes()) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        let multipv = limits.multipv.max(skill.multipv()).clamp(1, moves.len());
+        let deadline = match (limits.movetime.map(|t| Instant::now() + t), manager.as_ref().map(TimeManager::deadline)) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+
+        /* Lazy SMP, helper threads run the same iterative deepening
+         * sharing the table, filling it ahead of the main thread
+         * until it finishes. In wasm the rayon pool only has more
+         * than one thread in a build with the parallel feature, once
+         * initThreadPool has started it
+         */
+        let threads = limits.threads.unwrap_or_else(Engine::threads).max(1);
+        let owned;
+        let tt = match &limits.table {
+            Some(table) => table,
+            None => {
+                owned = TranspositionTable::new(HASH_MEGABYTES);
+                &owned
+            },
+        };
+        let stop = AtomicBool::new(false);
+        let nodes = AtomicU64::new(0);
+        let search = Search {
+            deadline,
+            stop: &stop,
+            tt,
+            selectivity: limits.selectivity,
+            depth: 0,
+            nodes: &nodes,
+            max_nodes,
+            noise: skill.noise(),
+            seed: limits.seed,
+            config: limits.config,
+            network: limits.network.as_deref(),
+            ponder: limits.ponder.as_deref(),
+        };
+
+        let (mut lines, mut completed) = (vec![(0, 0)], 0);
+        rayon::scope(|s| {
+            for id in 1..threads {
+                let (search, moves) = (&search, &moves);
+                s.spawn(move |_| {
+                    // every other helper starts a ply deeper
+                    let mut last = None;
+                    for d in 1 + (id % 2) as u8..=u8::MAX {
+                        match Engine::aspiration(game, moves, d, last, search, id, 1) {
+                            Ok(lines) => last = Some(lines[0].1),
+                            Err(_) => break,
+                        }
+                    }
+                });
+            }
+
+            // the first depth always finishes, so the move played was searched
+            let first = Search { deadline: None, max_nodes: None, ponder: None, ..search };
+            let mut last = None;
+            for d in 1..=depth {
+                let searching = if d == 1 { &first } else { &search };
+                match Engine::aspiration(game, &moves, d, last, searching, 0, multipv) {
+                    Ok(scored) => (last, lines, completed) = (Some(scored[0].1), scored, d),
+                    Err(_) => break,
+                }
+                // the first depth's copy has no ponder to keep the search going
+                if search.stopped() { break; }
 
-        let result = Arc::new(Mutex::new(Vec::<(usize, i32)>::new()));
+                let Some(manager) = manager.as_mut() else { continue };
+                let (best, score) = lines[0];
+                if d == EASY_DEPTH && multipv == 1 && moves[best].bits() & info.opp_pieces != 0
+                    && Engine::easy(game, &moves, best, d, score, &search) {
+                    manager.easy_move();
+                }
+                // pondering goes on deeper, the time spent counts after the hit
+                if !manager.next_depth(best, score) && !search.pondering() { break; }
+            }
+
+            // the move is only given once the reply is played or missed
+            while search.pondering() && !search.stopped() {
+                std::thread::sleep(Duration::from_millis(1));
+            }
+            stop.store(true, Relaxed);
+        });
 
-        // less moves to calc means
-        // greater depth possible
+        let (i, score) = lines[skill.pick(&lines, limits.seed)];
+        Ok(SearchResult {
+            ponder: Engine::reply(game, &moves[i], tt),
+            best_move: moves[i].clone(),
+            score: Score::from_eval(score),
+            depth: completed,
+            nodes: nodes.load(Relaxed),
+            lines: lines.iter().map(|(i, score)| (moves[*i].clone(), Score::from_eval(*score))).collect(),
+        })
+    }
+
+    /* The shortest forced mate within the limit's moves,
+     * None when every line was searched and there is none
+     */
+    pub fn find_mate(game: &Game, limits: &MateLimits) -> Result<Option<MateTree>, EngineError> {
+        let info = game.info()?;
+        if info.checkmate {
+            return Err(EngineError::Checkmate);
+        }
+        if info.stalemate {
+            return Err(EngineError::Stalemate);
+        }
+        MateSearch::new(limits).shortest(game, limits.moves)
+    }
+
+    // the pool's threads, the browser only has a pool with the parallel feature
+    #[cfg(not(all(target_arch = "wasm32", not(feature = "parallel"))))]
+    fn threads() -> usize {
+        rayon::current_num_threads()
+    }
+
+    #[cfg(all(target_arch = "wasm32", not(feature = "parallel")))]
+    fn threads() -> usize {
+        1
+    }
+
+    // the reply the table expects to a move, to ponder on
+    fn reply(game: &Game, mv: &Pieces, tt: &TranspositionTable) -> Option<Pieces> {
+        let mut child = game.clone();
+        child.move_piece(*mv.bits());
+        let e = tt.probe(child.hash())?;
+        child.moves_verbose().ok()?.0.into_iter().nth(e.best as usize)
+    }
+
+    /* Whether every other move falls short of the best by the
+     * margin at half the depth, null windows so it is cheap.
+     * Nothing is stored for the root, it holds only some moves
+     */
+    fn easy(game: &Game, moves: &[Pieces], best: usize, depth: u8, score: i32, search: &Search) -> bool {
+        let search = &Search { depth: depth / 2, ..*search };
+        let root = search.network.map(|network| network.refresh(game));
+        let floor = score.saturating_sub(EASY_MARGIN);
+        moves.iter().enumerate().filter(|(i, _)| *i != best).all(|(_, mv)| {
+            let mut game_copy = game.clone();
+            game_copy.move_piece(*mv.bits());
+            let child = search.update(root.as_ref(), game, &game_copy);
+            matches!(pvs(&game_copy, child.as_ref(), false, depth / 2 - 1, 1, floor, floor + 1, search), Ok(s) if s <= floor)
+        })
+    }
+
+    // less moves to calc means
+    // greater depth possible
+    fn depth(game: &Game) -> u8 {
         let mut cost = 0u8; // if the calc is expensive
-        for piece in &game.pieces {
+        for piece in game.pieces() {
             match piece {
                 Pieces::Pawn(_) => cost += 1,
                 Pieces::Bishop(_) => cost += 3,
@@ -38,41 +283,88 @@ impl Engine {
             }
         }
 
-        let depth;
+        // selective search affords two plies more than full width
         if cost > 50 {
-            depth = 2;
+            5
         } else if cost > 24 {
-            depth = 3;
+            6
         } else if cost > 12 {
-            depth = 4;
+            7
         } else {
-            depth = 5;
+            8
         }
+    }
 
-        // the leaves will be different at odd
-        // and even depths so a factor is applied
-        let mut factor = 1;
-        if depth % 2 == 0 {
-            factor = -1;
-        }
+    /* Searches a depth in a window around the last depth's score,
+     * widening the side that fails until the score falls inside.
+     * More than one line is searched with the full window
+     */
+    fn aspiration(game: &Game, moves: &[Pieces], depth: u8, last: Option<i32>, search: &Search, id: usize, multipv: usize) -> Result<Vec<(usize, i32)>, EngineError> {
+        let mut delta = ASPIRATION;
+        let (mut alpha, mut beta) = match last {
+            Some(score) if multipv == 1 => (score.saturating_sub(delta).max(-INFINITY), score.saturating_add(delta)),
+            _ => (-INFINITY, INFINITY),
+        };
 
-        moves.par_iter().enumerate().for_each(|(i, mv)| {
-            let mut game_copy = game.clone();
-            game_copy.move_piece(*mv.bits());
-            let eval = minimax(game_copy.clone(), false, depth, i32::MIN, i32::MAX, factor).unwrap_or_else(|_| {
-                minimax(game_copy.clone(), false, depth-1, i32::MIN, i32::MAX, -factor).unwrap()
-            });
-            let result_copy = result.clone();
-            result_copy.lock().unwrap().push((i, eval));
-        });
+        loop {
+            let mut lines = Engine::search_root(game, moves, depth, alpha, beta, search, id, multipv)?;
+            let score = lines[0].1;
+            if score <= alpha && alpha > -INFINITY {
+                alpha = score.saturating_sub(delta).max(-INFINITY);
+            } else if score >= beta && beta < INFINITY {
+                beta = score.saturating_add(delta);
+            } else {
+                lines.truncate(multipv);
+                return Ok(lines);
+            }
+            delta = delta.saturating_mul(4);
+        }
+    }
 
-        let mut best_move = (0, i32::MIN);
-        for mv in result.lock().unwrap().iter() {
-            if mv.1 > best_move.1 {
-                best_move = (mv.0, mv.1);
+    /* Returns the moves' indices and scores at the given depth, best
+     * first. The first multipv scores are exact, the rest only
+     * bounds. The table's best move goes first and helper
+     * threads start further along the list
+     */
+    #[allow(clippy::too_many_arguments)]
+    fn search_root(game: &Game, moves: &[Pieces], depth: u8, alpha: i32, beta: i32, search: &Search, id: usize, multipv: usize) -> Result<Vec<(usize, i32)>, EngineError> {
+        let search = &Search { depth, ..*search };
+        let root = search.network.map(|network| network.refresh(game));
+        let key = game.hash();
+        let mut order: Vec<usize> = (0..moves.len()).collect();
+        order.rotate_left(id % moves.len());
+        if let Some(e) = search.tt.probe(key) {
+            if let Some(p) = order.iter().position(|i| *i == e.best as usize) {
+                order.remove(p);
+                order.insert(0, e.best as usize);
             }
         }
 
-        Ok(moves[best_move.0].clone())
+        // a move only has to beat the last of the lines kept
+        let mut lines: Vec<(usize, i32)> = Vec::with_capacity(moves.len());
+        for i in order {
+            let floor = match lines.get(multipv - 1) {
+                Some(line) => alpha.max(line.1),
+                None => alpha,
+            };
+            let mut game_copy = game.clone();
+            game_copy.move_piece(*moves[i].bits());
+            let child = search.update(root.as_ref(), game, &game_copy);
+            let score = pvs(&game_copy, child.as_ref(), lines.len() < multipv, depth - 1, 1, floor, beta, search)?;
+            let at = lines.partition_point(|line| line.1 >= score);
+            lines.insert(at, (i, score));
+            if score >= beta { break; }
+        }
+
+        let (best, score) = lines[0];
+        let bound = if score >= beta {
+            Bound::Lower
+        } else if score <= alpha {
+            Bound::Upper
+        } else {
+            Bound::Exact
+        };
+        search.tt.store(key, Entry { score, depth, bound, best: best as u8 });
+        Ok(lines)
     }
 }
@@ -1,130 +1,357 @@
-use crate::game::{Game, pieces::{Piece, Pieces}, util::GameInfo};
+use crate::game::{
+    Game,
+    attacks,
+    board::{Bits, index, east, west, FILE_A},
+    pieces::{Color, Kind, Piece, Pieces},
+    util::GameInfo,
+};
+use super::{centipawns, from_centipawns, EngineConfig, MATE, weights::WEIGHTS};
 
+// the unit of evaluation, fixed so tuning cannot rescale it
 pub const PAWN_VAL: i32 = 2;
-pub const BISHOP_VAL: i32 = 6;
-pub const KNIGHT_VAL: i32 = 6;
-pub const ROOK_VAL: i32 = 10;
-pub const QUEEN_VAL: i32 = 18;
-pub const HOT_ZONE: u128 = 0x0000_0000_2400_3c00_3c00_2400_0000_0000;
-pub const KING_SAFETY: u128 = 0x42 << 0x08 | 0x42 << 0x78;
-
-#[derive(PartialEq)]
-enum GameState {
+pub const HOT_ZONE: u64 = 0x0000_243c_3c24_0000;
+pub const KING_SAFETY: u64 = 0x42 | 0x42 << 0x38;
+
+// for being ahead in material before the endgame
+const AHEAD: i32 = 2;
+
+/* The evaluation's terms, tuned by the tune binary which
+ * writes them to weights.rs. Hot zone is for a piece in the
+ * centre early on, king safety for a castled king, the
+ * pawn terms are in quarter pawns and the rest are taken
+ * off for being in check or short of moves. The pawn terms
+ * stay at nothing, so only traced, until they are tuned
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Weights {
+    pub bishop: i32,
+    pub knight: i32,
+    pub rook: i32,
+    pub queen: i32,
+    pub hot_zone: i32,
+    pub king_safety: i32,
+    pub check: i32,
+    pub double_check: i32,
+    pub check_few_moves: i32,
+    pub few_moves: i32,
+    pub doubled: i32,
+    pub isolated: i32,
+    pub passed: i32,
+}
+
+impl Weights {
+    pub const NAMES: [&'static str; 13] = [
+        "bishop", "knight", "rook", "queen", "hot_zone", "king_safety",
+        "check", "double_check", "check_few_moves", "few_moves",
+        "doubled", "isolated", "passed",
+    ];
+
+    pub fn to_array(&self) -> [i32; 13] {
+        [
+            self.bishop, self.knight, self.rook, self.queen, self.hot_zone, self.king_safety,
+            self.check, self.double_check, self.check_few_moves, self.few_moves,
+            self.doubled, self.isolated, self.passed,
+        ]
+    }
+
+    pub fn from_array(a: [i32; 13]) -> Self {
+        Weights {
+            bishop: a[0], knight: a[1], rook: a[2], queen: a[3], hot_zone: a[4], king_safety: a[5],
+            check: a[6], double_check: a[7], check_few_moves: a[8], few_moves: a[9],
+            doubled: a[10], isolated: a[11], passed: a[12],
+        }
+    }
+
+    // each side's material at the start
+    fn material(&self) -> i32 {
+        8 * PAWN_VAL + 2 * (self.bishop + self.knight + self.rook) + self.queen
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GameState {
     Early,
     Mid,
     Late
 }
 
+impl GameState {
+    pub const ALL: [GameState; 3] = [GameState::Early, GameState::Mid, GameState::Late];
+
+    pub fn of(game: &Game) -> Self {
+        match game.move_count {
+            0..=16 => GameState::Early,
+            17..=32 => GameState::Mid,
+            _ => GameState::Late,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameState::Early => "opening",
+            GameState::Mid => "middlegame",
+            GameState::Late => "endgame",
+        }
+    }
+}
+
+// a term of the evaluation for each side, positive is good for that side
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Term {
+    pub white: i32,
+    pub black: i32,
+}
+
+impl Term {
+    fn add(&mut self, color: Color, value: i32) {
+        match color {
+            Color::White => self.white += value,
+            Color::Black => self.black += value,
+        }
+    }
+
+    // the side to move's less the other side's
+    pub fn score(&self, turn: Color) -> i32 {
+        match turn {
+            Color::White => self.white - self.black,
+            Color::Black => self.black - self.white,
+        }
+    }
+}
+
+/* The evaluation term by term. Piece square is the hot zone early
+ * on, pawn structure doubled, isolated and passed pawns in quarter
+ * pawns, rounded to the evaluation's unit once summed, castled
+ * king the king tucked away and king attack the pieces bearing on
+ * the enemy king. Mobility is being in check or short of moves,
+ * ahead the flat bonus for more material before the endgame,
+ * simplification trading down when ahead and fifty moves what
+ * drifting towards the fifty move draw costs the side ahead
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Trace {
+    pub turn: Color,
+    pub state: GameState,
+    pub material: Term,
+    pub piece_square: Term,
+    pub pawn_structure: Term,
+    pub castled_king: Term,
+    pub king_attack: Term,
+    pub mobility: Term,
+    pub ahead: Term,
+    pub simplification: Term,
+    pub fifty_moves: Term,
+    pub checkmate: bool,
+}
+
+impl Trace {
+    pub const NAMES: [&'static str; 9] = [
+        "material", "piece_square", "pawn_structure", "castled_king", "king_attack",
+        "mobility", "ahead", "simplification", "fifty_moves",
+    ];
+
+    // what each term knows about, the endgame terms turn a lead in material into a win
+    pub const GROUPS: [&'static str; 9] = [
+        "material", "piece_square", "pawn_structure", "king_safety", "king_safety",
+        "mobility", "endgame", "endgame", "endgame",
+    ];
+
+    pub fn terms(&self) -> [Term; 9] {
+        [
+            self.material, self.piece_square, self.pawn_structure, self.castled_king, self.king_attack,
+            self.mobility, self.ahead, self.simplification, self.fifty_moves,
+        ]
+    }
+
+    // what evaluate returns, for the side to move
+    pub fn score(&self) -> i32 {
+        // the search adds the distance from the root
+        if self.checkmate { return -MATE; }
+        let pawns = self.pawn_structure.score(self.turn);
+        let terms: i32 = self.terms().iter().map(|t| t.score(self.turn)).sum();
+        terms - pawns + from_quarters(pawns)
+    }
+
+    // each term in centipawns, the pawn structure from its quarter pawns
+    pub fn centipawns(&self) -> [Term; 9] {
+        let mut terms = self.terms().map(|t| Term { white: centipawns(t.white), black: centipawns(t.black) });
+        terms[2] = Term { white: self.pawn_structure.white * 25, black: self.pawn_structure.black * 25 };
+        terms
+    }
+}
+
+// quarter pawns to the evaluation's unit, halves rounded away from zero alike for both sides
+fn from_quarters(quarters: i32) -> i32 {
+    let value = quarters * PAWN_VAL;
+    (value + 2 * value.signum()) / 4
+}
+
+// pieces of the color attacking the enemy king or the squares around it
+fn king_attackers(game: &Game, color: Color) -> i32 {
+    let king = game.board.get(Kind::King, color.opponent());
+    if king == 0 { return 0; }
+    let zone = king | attacks::king(index(king));
+    let occupied = game.board.occupied();
+
+    let mut attackers = 0;
+    for kind in [Kind::Knight, Kind::Bishop, Kind::Rook, Kind::Queen] {
+        for bit in Bits(game.board.get(kind, color)) {
+            let square = index(bit);
+            let reach = match kind {
+                Kind::Knight => attacks::knight(square),
+                Kind::Bishop => attacks::bishop(square, occupied),
+                Kind::Rook => attacks::rook(square, occupied),
+                _ => attacks::queen(square, occupied),
+            };
+            if reach & zone != 0 { attackers += 1; }
+        }
+    }
+    attackers
+}
+
+#[must_use]
+// scored for the side to move, weighted by the config
+pub fn evaluate(game: &Game, info: &GameInfo, config: &EngineConfig) -> i32 {
+    evaluate_with(game, info, config, &WEIGHTS)
+}
+
+#[must_use]
+pub fn evaluate_with(game: &Game, info: &GameInfo, config: &EngineConfig, weights: &Weights) -> i32 {
+    evaluate_traced_with(game, info, config, weights).score()
+}
+
 #[must_use]
-pub fn evaluate(game: &Game, info: &GameInfo, factor: i32) -> i32 {
-    let mut eval = 0i32;
-    let mut team = 0i32;
-    let mut opp = 0i32;
-
-    let game_state = match game.move_count {
-        0..=16 => GameState::Early,
-        17..=32 => GameState::Mid,
-        _ => GameState::Late,
+pub fn evaluate_traced(game: &Game, info: &GameInfo, config: &EngineConfig) -> Trace {
+    evaluate_traced_with(game, info, config, &WEIGHTS)
+}
+
+#[must_use]
+pub fn evaluate_traced_with(game: &Game, info: &GameInfo, config: &EngineConfig, weights: &Weights) -> Trace {
+    evaluate_traced_in(game, info, config, weights, GameState::of(game))
+}
+
+// the files either side of a file
+fn neighbours(file: u32) -> u64 {
+    west(FILE_A << file) | east(FILE_A << file)
+}
+
+/* Doubled and isolated pawns cost and passed pawns earn, in
+ * quarter pawns as a half pawn is too coarse for them
+ */
+fn pawn_structure(game: &Game, color: Color, weights: &Weights) -> i32 {
+    let ours = game.board.get(Kind::Pawn, color);
+    let theirs = game.board.get(Kind::Pawn, color.opponent());
+
+    let mut quarters = 0;
+    for file in 0..8 {
+        let on_file = (ours & FILE_A << file).count_ones() as i32;
+        if on_file == 0 { continue; }
+        quarters -= (on_file - 1) * weights.doubled;
+        if ours & neighbours(file) == 0 {
+            quarters -= on_file * weights.isolated;
+        }
+    }
+
+    for bit in Bits(ours) {
+        let (file, rank) = (index(bit) as u32 % 8, index(bit) as u32 / 8);
+        // the ranks in front of the pawn from its own side
+        let front = match color {
+            Color::White => 1u64.checked_shl((rank + 1) * 8).map_or(0, |b| !(b - 1)),
+            Color::Black => (1u64 << (rank * 8)) - 1,
+        };
+        if theirs & front & (FILE_A << file | neighbours(file)) == 0 {
+            quarters += weights.passed;
+        }
+    }
+    quarters
+}
+
+// the trace as though the game were in the given state
+#[must_use]
+pub fn evaluate_traced_in(game: &Game, info: &GameInfo, config: &EngineConfig, weights: &Weights, state: GameState) -> Trace {
+    let turn = game.turn;
+    let mut trace = Trace {
+        turn,
+        state,
+        material: Term::default(),
+        piece_square: Term::default(),
+        pawn_structure: Term::default(),
+        castled_king: Term::default(),
+        king_attack: Term::default(),
+        mobility: Term::default(),
+        ahead: Term::default(),
+        simplification: Term::default(),
+        fifty_moves: Term::default(),
+        checkmate: info.checkmate,
     };
 
-    if info.double_check == true {
-        eval -= 16;
+    if info.double_check {
+        trace.mobility.add(turn, -weights.double_check);
     }
-    else if info.check == true && info.valid_moves < 3 {
-        eval -= 16;
+    else if info.check && info.valid_moves < 3 {
+        trace.mobility.add(turn, -weights.check_few_moves);
     }
-    else if info.check == true {
-        eval -= 2;
+    else if info.check {
+        trace.mobility.add(turn, -weights.check);
     }
     else if info.valid_moves < 3 {
-        eval -= 5;
-    }
-
-    for piece in &game.pieces {
-        if *piece.color() == game.turn {
-            match piece {
-                Pieces::Pawn(p) => {
-                    team += PAWN_VAL;
-                    if game_state == GameState::Early && p.bits() & HOT_ZONE != 0 {
-                        eval += 1;
-                    }
-                },
-                Pieces::Bishop(b) => {
-                    team += BISHOP_VAL;
-                    if game_state == GameState::Early && b.bits() & HOT_ZONE != 0 {
-                        eval += 1;
-                    }
-                },
-                Pieces::Knight(n) => {
-                    team += KNIGHT_VAL;
-                    if game_state == GameState::Early && n.bits() & HOT_ZONE != 0 {
-                        eval += 1;
-                    }
-                },
-                Pieces::Rook(_) => {
-                    team += ROOK_VAL;
-                },
-                Pieces::Queen(_) => {
-                    team += QUEEN_VAL;
-                },
-                Pieces::King(k) => {
-                    if k.bits() & KING_SAFETY != 0 {
-                        eval += 2;
-                    }
-                },
-            }
-        } else { // opp
-            match piece {
-                Pieces::Pawn(p) => {
-                    opp += PAWN_VAL;
-                    if game_state == GameState::Early && p.bits() & HOT_ZONE != 0 {
-                        eval -= 1;
-                    }
-                },
-                Pieces::Bishop(b) => {
-                    opp += BISHOP_VAL;
-                    if game_state == GameState::Early && b.bits() & HOT_ZONE != 0 {
-                        eval -= 1;
-                    }
-                },
-                Pieces::Knight(n) => {
-                    opp += KNIGHT_VAL;
-                    if game_state == GameState::Early && n.bits() & HOT_ZONE != 0 {
-                        eval -= 1;
-                    }
-                },
-                Pieces::Rook(_) => {
-                    opp += ROOK_VAL;
-                },
-                Pieces::Queen(_) => {
-                    opp += QUEEN_VAL;
-                },
-                Pieces::King(k) => {
-                    if k.bits() & KING_SAFETY != 0 {
-                        eval -= 2;
-                    }
-                },
-            }
+        trace.mobility.add(turn, -weights.few_moves);
+    }
+
+    let mut material = Term::default();
+    for piece in game.pieces() {
+        let color = *piece.color();
+        let (value, hot) = match piece {
+            Pieces::Pawn(p) => (PAWN_VAL, p.bits() & HOT_ZONE != 0),
+            Pieces::Bishop(b) => (weights.bishop, b.bits() & HOT_ZONE != 0),
+            Pieces::Knight(n) => (weights.knight, n.bits() & HOT_ZONE != 0),
+            Pieces::Rook(_) => (weights.rook, false),
+            Pieces::Queen(_) => (weights.queen, false),
+            Pieces::King(k) => {
+                if k.bits() & KING_SAFETY != 0 {
+                    trace.castled_king.add(color, weights.king_safety);
+                }
+                (0, false)
+            },
+        };
+        material.add(color, value);
+        if state == GameState::Early && hot {
+            trace.piece_square.add(color, weights.hot_zone);
         }
     }
 
-    eval += team;
-    eval -= opp;
-    if team > opp && game_state != GameState::Late {
-        eval += 2;
+    trace.material = Term {
+        white: material.white * config.materialism / 100,
+        black: material.black * config.materialism / 100,
+    };
+    trace.pawn_structure = Term {
+        white: pawn_structure(game, Color::White, weights),
+        black: pawn_structure(game, Color::Black, weights),
+    };
+
+    let (team, opp) = match turn {
+        Color::White => (material.white, material.black),
+        Color::Black => (material.black, material.white),
+    };
+    if team > opp && state != GameState::Late {
+        trace.ahead.add(turn, AHEAD);
     }
 
-    if info.checkmate == true {
-        eval = -1_000_000 + game.move_count as i32;
+    // trading down when ahead, worth more the less the opponent has left
+    if team > opp && config.simplification != 0 {
+        let total = weights.material();
+        trace.simplification.add(turn, config.simplification * PAWN_VAL * (total - opp).max(0) / (100 * total));
     }
-    if info.valid_moves == 0 && info.check {
-        eval = -1_000_000 + game.move_count as i32;
+
+    if config.aggressiveness != 0 {
+        for color in [Color::White, Color::Black] {
+            trace.king_attack.add(color, from_centipawns(king_attackers(game, color) * config.aggressiveness));
+        }
     }
 
     if game.half_moves > 10 && team > opp {
-        eval -= game.half_moves as i32;
+        trace.fifty_moves.add(turn, -(game.half_moves as i32));
     }
 
-    // factor used for variable depths
-    return eval * factor;
+    trace
 }
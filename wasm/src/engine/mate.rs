@@ -0,0 +1,143 @@
+use std::{
+    sync::{Arc, atomic::{AtomicBool, Ordering::Relaxed}},
+    time::Duration,
+};
+use web_time::Instant;
+use crate::game::{
+    Game,
+    pieces::{Piece, Pieces},
+    util::GameInfo,
+};
+
+use super::EngineError;
+
+/* Limits of a mate search, moves counts the attacker's moves.
+ * With checks_only every attacking move gives check, a much
+ * smaller tree, but then finding none does not prove there
+ * is no quiet mate. Setting stop ends the search early
+ */
+#[derive(Clone, Default)]
+pub struct MateLimits {
+    pub moves: u8,
+    pub checks_only: bool,
+    pub movetime: Option<Duration>,
+    pub stop: Option<Arc<AtomicBool>>,
+}
+
+/* A forced mate, the attacker's move and every defence to it
+ * each followed by the shortest mate left. The mating
+ * move itself has no defences
+ */
+#[derive(Clone)]
+pub struct MateTree {
+    pub mv: Pieces,
+    pub moves: u8, // attacker's moves to mate, this one included
+    pub defences: Vec<(Pieces, MateTree)>,
+}
+
+impl MateTree {
+    // the main line, each defence is the one holding out longest
+    pub fn line(&self) -> Vec<Pieces> {
+        let mut line = vec![self.mv.clone()];
+        if let Some((defence, tree)) = self.defences.iter().max_by_key(|(_, t)| t.moves) {
+            line.push(defence.clone());
+            line.extend(tree.line());
+        }
+        line
+    }
+}
+
+// slots for refuted positions, a fixed size so a long search stays in memory
+const REFUTED_SLOTS: usize = 1 << 18;
+
+/* Proves mates by trying every attacking move against every
+ * defence, a move only mates in n if each reply is mated
+ * in n - 1. Positions shown to have no mate are remembered
+ * so transpositions and the next iteration skip them, a
+ * position sharing a slot overwrites the one before
+ */
+pub(super) struct MateSearch {
+    checks_only: bool,
+    deadline: Option<Instant>,
+    stop: Option<Arc<AtomicBool>>,
+    refuted: Vec<(u64, u8)>, // key and no mate within this many moves
+}
+
+impl MateSearch {
+    pub fn new(limits: &MateLimits) -> Self {
+        MateSearch {
+            checks_only: limits.checks_only,
+            deadline: limits.movetime.map(|t| Instant::now() + t),
+            stop: limits.stop.clone(),
+            refuted: vec![(0, 0); REFUTED_SLOTS],
+        }
+    }
+
+    // tries each length in turn so the first mate found is the shortest
+    pub fn shortest(&mut self, game: &Game, moves: u8) -> Result<Option<MateTree>, EngineError> {
+        for n in 1..=moves {
+            if let Some(tree) = self.attack(game, n)? {
+                return Ok(Some(tree));
+            }
+        }
+        Ok(None)
+    }
+
+    fn attack(&mut self, game: &Game, n: u8) -> Result<Option<MateTree>, EngineError> {
+        if self.deadline.is_some_and(|t| Instant::now() >= t) || self.stop.as_ref().is_some_and(|s| s.load(Relaxed)) {
+            return Err(EngineError::OutOfTime);
+        }
+        let key = game.hash();
+        let slot = key as usize % REFUTED_SLOTS;
+        if self.refuted[slot].0 == key && self.refuted[slot].1 >= n {
+            return Ok(None);
+        }
+
+        /* Only a check can mate on the last move, elsewhere checks
+         * go first and then the moves leaving the fewest replies
+         */
+        let mut candidates = Vec::new();
+        for mv in game.moves()? {
+            let mut next = game.clone();
+            next.move_piece(*mv.bits());
+            if (n == 1 || self.checks_only) && !GameInfo::init(&next).is_ok_and(|i| i.check) {
+                continue;
+            }
+            let (replies, info) = next.moves_verbose()?;
+            if info.checkmate {
+                return Ok(Some(MateTree { mv, moves: 1, defences: Vec::new() }));
+            }
+            if n > 1 && !info.stalemate {
+                candidates.push((!info.check, replies.len(), mv, next, replies));
+            }
+        }
+        candidates.sort_by_key(|c| (c.0, c.1));
+
+        for (_, _, mv, next, replies) in candidates {
+            if let Some(defences) = self.defend(&next, replies, n - 1)? {
+                let moves = 1 + defences.iter().map(|(_, t)| t.moves).max().unwrap_or(0);
+                return Ok(Some(MateTree { mv, moves, defences }));
+            }
+        }
+
+        let refuted = &mut self.refuted[slot];
+        *refuted = if refuted.0 == key { (key, refuted.1.max(n)) } else { (key, n) };
+        Ok(None)
+    }
+
+    /* The mate after every reply, None once a reply escapes. Only the
+     * root needs the shortest, below it any mate within n will do
+     */
+    fn defend(&mut self, game: &Game, replies: Vec<Pieces>, n: u8) -> Result<Option<Vec<(Pieces, MateTree)>>, EngineError> {
+        let mut defences = Vec::with_capacity(replies.len());
+        for reply in replies {
+            let mut next = game.clone();
+            next.move_piece(*reply.bits());
+            match self.attack(&next, n)? {
+                Some(tree) => defences.push((reply, tree)),
+                None => return Ok(None),
+            }
+        }
+        Ok(Some(defences))
+    }
+}
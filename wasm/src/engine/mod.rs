@@ -3,20 +3,89 @@ use std::{
     fmt
 };
 
+use crate::{
+    error::ErrorCode,
+    game::{GameError, fen::FenError},
+};
+
 pub mod engine;
-pub use engine::Engine;
-mod minimax;
-pub use minimax::minimax;
-mod evaluate;
-pub use evaluate::evaluate;
+pub use engine::{Engine, SearchLimits, SearchResult};
+mod negamax;
+pub use negamax::{negamax, Search, Selectivity, INFINITY, MATE};
+pub mod config;
+pub use config::EngineConfig;
+pub mod mate;
+pub use mate::{MateLimits, MateTree};
+pub mod nnue;
+pub use nnue::{Accumulator, Network};
+pub mod ponder;
+pub use ponder::Ponder;
+pub mod score;
+pub use score::{centipawns, from_centipawns, Score};
+pub mod skill;
+pub use skill::Skill;
+pub mod time;
+pub use time::{TimeControl, TimeManager};
+pub mod tt;
+pub use tt::TranspositionTable;
+pub mod evaluate;
+pub use evaluate::{evaluate, evaluate_traced, Trace, Weights};
+mod weights;
+pub use weights::WEIGHTS;
+pub mod tune;
+pub mod suite;
+pub use suite::{run_suite, SuiteReport};
 
-#[derive(Debug)]
-pub struct EngineError(String);
+#[derive(Debug, Clone, PartialEq)]
+pub enum EngineError {
+    Checkmate,
+    Stalemate,
+    OutOfTime,
+    Config(String),
+    Network(String),
+    Game(GameError),
+    Fen(FenError),
+}
 
 impl fmt::Display for EngineError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Engine Error: {}", self.0)
+        write!(f, "Engine Error: ")?;
+        match self {
+            EngineError::Checkmate => write!(f, "Checkmate"),
+            EngineError::Stalemate => write!(f, "Draw"),
+            EngineError::OutOfTime => write!(f, "Out of time"),
+            EngineError::Config(e) => write!(f, "Invalid config, {}", e),
+            EngineError::Network(e) => write!(f, "Invalid network, {}", e),
+            EngineError::Game(e) => write!(f, "{}", e),
+            EngineError::Fen(e) => write!(f, "{}", e),
+        }
     }
 }
 
 impl Error for EngineError {}
+
+impl ErrorCode for EngineError {
+    fn code(&self) -> &'static str {
+        match self {
+            EngineError::Checkmate => "ENGINE_CHECKMATE",
+            EngineError::Stalemate => "ENGINE_STALEMATE",
+            EngineError::OutOfTime => "ENGINE_OUT_OF_TIME",
+            EngineError::Config(_) => "ENGINE_CONFIG",
+            EngineError::Network(_) => "ENGINE_NETWORK",
+            EngineError::Game(e) => e.code(),
+            EngineError::Fen(e) => e.code(),
+        }
+    }
+}
+
+impl From<GameError> for EngineError {
+    fn from(e: GameError) -> Self {
+        EngineError::Game(e)
+    }
+}
+
+impl From<FenError> for EngineError {
+    fn from(e: FenError) -> Self {
+        EngineError::Fen(e)
+    }
+}
@@ -0,0 +1,322 @@
+use crate::game::{
+    Game,
+    board::{RANK_1, RANK_8},
+    pieces::{Kind, Piece, Pieces},
+    util::GameInfo,
+};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering::Relaxed};
+use web_time::Instant;
+
+use super::{
+    evaluate::{evaluate, PAWN_VAL},
+    nnue::{Accumulator, Network},
+    ponder::Ponder,
+    score::from_centipawns,
+    EngineConfig,
+    EngineError,
+    tt::{Bound, Entry, TranspositionTable},
+};
+
+// above any score, negating it stays in range
+pub const INFINITY: i32 = i32::MAX;
+
+/* Being mated scores -MATE plus the plies from the root,
+ * so a nearer mate is preferred and scores past the bound
+ * are mates. A pawn is worth 2
+ */
+pub const MATE: i32 = 1_000_000;
+pub const MATE_BOUND: i32 = 900_000;
+
+// margins for the pruning below, per ply of depth left
+const FUTILITY_MARGIN: i32 = 4;
+const REVERSE_FUTILITY_MARGIN: i32 = 3;
+const RAZOR_MARGIN: i32 = 6;
+
+/* Selective search, each part can be switched off for testing.
+ * All of them are on by default
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Selectivity {
+    pub null_move: bool,
+    pub late_move_reductions: bool,
+    pub futility: bool,
+    pub reverse_futility: bool,
+    pub razoring: bool,
+    pub check_extensions: bool,
+}
+
+impl Selectivity {
+    // every move searched to full depth
+    pub const NONE: Selectivity = Selectivity {
+        null_move: false,
+        late_move_reductions: false,
+        futility: false,
+        reverse_futility: false,
+        razoring: false,
+        check_extensions: false,
+    };
+}
+
+impl Default for Selectivity {
+    fn default() -> Self {
+        Selectivity {
+            null_move: true,
+            late_move_reductions: true,
+            futility: true,
+            reverse_futility: true,
+            razoring: true,
+            check_extensions: true,
+        }
+    }
+}
+
+/* What every thread of one search shares, depth is the iteration's.
+ * Noise blurs every evaluation by up to that much either way,
+ * the same for a position all search long, the seed picks how.
+ * With a network positions carry an accumulator for it
+ * and the config's style no longer applies. While pondering
+ * the deadline and nodes wait for the hit
+ */
+#[derive(Clone, Copy)]
+pub struct Search<'a> {
+    pub deadline: Option<Instant>,
+    pub stop: &'a AtomicBool,
+    pub tt: &'a TranspositionTable,
+    pub selectivity: Selectivity,
+    pub depth: u8,
+    pub nodes: &'a AtomicU64,
+    pub max_nodes: Option<u64>,
+    pub noise: i32,
+    pub seed: u64,
+    pub config: EngineConfig,
+    pub network: Option<&'a Network>,
+    pub ponder: Option<&'a Ponder>,
+}
+
+impl Search<'_> {
+    #[inline]
+    pub fn stopped(&self) -> bool {
+        if self.stop.load(Relaxed) || self.ponder.is_some_and(Ponder::is_stopped) { return true; }
+        if self.pondering() { return false; }
+        self.deadline.is_some_and(|t| Instant::now() >= t) ||
+        self.max_nodes.is_some_and(|n| self.nodes.load(Relaxed) >= n)
+    }
+
+    #[inline]
+    pub fn pondering(&self) -> bool {
+        self.ponder.is_some_and(Ponder::is_pondering)
+    }
+
+    #[inline]
+    pub fn evaluate(&self, game: &Game, info: &GameInfo, accumulator: Option<&Accumulator>) -> i32 {
+        let eval = match (self.network, accumulator) {
+            (Some(network), Some(accumulator)) => network.evaluate(accumulator, game.turn) * PAWN_VAL / 100,
+            _ => evaluate(game, info, &self.config),
+        };
+        if self.noise == 0 { return eval; }
+        let mut key = game.hash() ^ self.seed;
+        key ^= key >> 33;
+        key = key.wrapping_mul(0xff51_afd7_ed55_8ccd);
+        key ^= key >> 33;
+        eval + (key % (2 * self.noise as u64 + 1)) as i32 - self.noise
+    }
+
+    // the accumulator of a child position when there is a network
+    #[inline]
+    pub fn update(&self, accumulator: Option<&Accumulator>, parent: &Game, child: &Game) -> Option<Accumulator> {
+        self.network.zip(accumulator).map(|(network, a)| network.update(a, parent, child))
+    }
+
+    // a draw is worth the contempt less to the side that started the search
+    #[inline]
+    pub fn draw(&self, ply: u8) -> i32 {
+        let contempt = from_centipawns(self.config.contempt);
+        if ply & 1 == 0 { -contempt } else { contempt }
+    }
+}
+
+// captures and promotions are never pruned or reduced
+#[inline]
+fn quiet(game: &Game, info: &GameInfo, mv: &Pieces) -> bool {
+    if let Pieces::Pawn(_) = mv {
+        if mv.bits() & (RANK_1 | RANK_8 | game.en_passant_square) != 0 { return false; }
+    }
+    mv.bits() & info.opp_pieces == 0
+}
+
+#[inline]
+fn gives_check(game: &Game) -> bool {
+    GameInfo::init(game).is_ok_and(|i| i.check)
+}
+
+/* Principal variation search, the first move is searched
+ * with the full window and the rest with a null window
+ * around alpha, only re-searching a move that beats it
+ */
+#[inline]
+#[allow(clippy::too_many_arguments)]
+pub fn pvs(game: &Game, accumulator: Option<&Accumulator>, first: bool, depth: u8, ply: u8, alpha: i32, beta: i32, search: &Search) -> Result<i32, EngineError> {
+    if first {
+        return Ok(-negamax(game, accumulator, depth, ply, -beta, -alpha, true, search)?);
+    }
+    let score = -negamax(game, accumulator, depth, ply, -alpha - 1, -alpha, true, search)?;
+    if score > alpha && score < beta {
+        return Ok(-negamax(game, accumulator, depth, ply, -beta, -alpha, true, search)?);
+    }
+    Ok(score)
+}
+
+/* The table keeps mate scores as plies from its position
+ * instead of the root, the same position is reached at
+ * different plies
+ */
+#[inline]
+fn to_table(score: i32, ply: u8) -> i32 {
+    if score >= MATE_BOUND { score + ply as i32 }
+    else if score <= -MATE_BOUND { score - ply as i32 }
+    else { score }
+}
+
+#[inline]
+fn from_table(score: i32, ply: u8) -> i32 {
+    if score >= MATE_BOUND { score - ply as i32 }
+    else if score <= -MATE_BOUND { score + ply as i32 }
+    else { score }
+}
+
+// scores from the side to move's view, fail-soft
+#[allow(clippy::too_many_arguments)]
+pub fn negamax(game: &Game, accumulator: Option<&Accumulator>, mut depth: u8, ply: u8, mut alpha: i32, mut beta: i32, allow_null: bool, search: &Search) -> Result<i32, EngineError> {
+    if search.stopped() {
+        return Err(EngineError::OutOfTime);
+    }
+    search.nodes.fetch_add(1, Relaxed);
+
+    // no line from here can beat a mate already found nearer the root
+    alpha = alpha.max(-MATE + ply as i32);
+    beta = beta.min(MATE - ply as i32 - 1);
+    if alpha >= beta {
+        return Ok(alpha);
+    }
+
+    let (moves, info) = game.moves_verbose()?;
+    if info.checkmate {
+        return Ok(-MATE + ply as i32);
+    }
+    if info.stalemate || game.half_moves >= 100 {
+        return Ok(search.draw(ply));
+    }
+
+    // a checked king is searched a ply deeper, bounded so
+    // a run of checks cannot extend the search forever
+    if info.check && search.selectivity.check_extensions && ply < search.depth.saturating_mul(2) {
+        depth += 1;
+    }
+
+    if depth == 0 || info.valid_moves == 0 {
+        return Ok(search.evaluate(game, &info, accumulator));
+    }
+
+    let key = game.hash();
+    let mut order: Vec<usize> = (0..moves.len()).collect();
+    if let Some(e) = search.tt.probe(key) {
+        let score = from_table(e.score, ply);
+        if e.depth >= depth {
+            match e.bound {
+                Bound::Exact => return Ok(score),
+                Bound::Lower if score >= beta => return Ok(score),
+                Bound::Upper if score <= alpha => return Ok(score),
+                _ => (),
+            }
+        }
+        // the best move found before is tried first
+        if (e.best as usize) < order.len() {
+            order.remove(e.best as usize);
+            order.insert(0, e.best as usize);
+        }
+    }
+
+    let pv = alpha + 1 < beta;
+    let selective = !pv && !info.check && beta.abs() < MATE_BOUND;
+    let eval = search.evaluate(game, &info, accumulator);
+    let s = &search.selectivity;
+
+    // far enough above beta that a quiet search would not fall below it
+    if selective && s.reverse_futility && depth <= 3 && eval - REVERSE_FUTILITY_MARGIN * depth as i32 >= beta {
+        return Ok(eval);
+    }
+
+    /* Null move, if passing still leaves the opponent below beta
+     * a real move will too. Kings and pawns alone are often in
+     * zugzwang, where passing would be the best move, so skip it
+     */
+    let pieces = info.team_pieces & !game.board.get(Kind::Pawn, game.turn) & !info.king.bits();
+    if selective && s.null_move && allow_null && depth >= 3 && eval >= beta && pieces != 0 {
+        let r = if depth > 6 { 3 } else { 2 };
+        let mut null = game.clone();
+        null.null_move();
+        let score = -negamax(&null, accumulator, depth - 1 - r.min(depth - 1), ply + 1, -beta, -beta + 1, false, search)?;
+        if score >= beta {
+            return Ok(if score >= MATE_BOUND { beta } else { score });
+        }
+    }
+
+    // hopelessly below alpha near the leaves, search a ply less
+    if selective && s.razoring && depth <= 2 && eval + RAZOR_MARGIN * depth as i32 <= alpha {
+        depth -= 1;
+        if depth == 0 { return Ok(eval); }
+    }
+
+    // quiet moves at the last ply cannot lift eval up to alpha
+    let futile = selective && s.futility && depth == 1 && eval + FUTILITY_MARGIN <= alpha;
+
+    let alpha_start = alpha;
+    let mut best = -INFINITY;
+    let mut best_index = order[0];
+
+    for (n, i) in order.into_iter().enumerate() {
+        let mut game_copy = game.clone();
+        game_copy.move_piece(*moves[i].bits());
+
+        let quiet = n > 0 && !info.check && quiet(game, &info, &moves[i]);
+        if futile && quiet && !gives_check(&game_copy) {
+            continue;
+        }
+        let child = search.update(accumulator, game, &game_copy);
+
+        /* Late moves are ordered last for a reason, they get a
+         * shallower null window search first and are only
+         * searched fully when that beats alpha
+         */
+        let mut score = None;
+        if s.late_move_reductions && quiet && n >= 3 && depth >= 3 && !gives_check(&game_copy) {
+            let r = if n >= 6 && depth >= 5 { 2 } else { 1 };
+            let reduced = -negamax(&game_copy, child.as_ref(), depth - 1 - r, ply + 1, -alpha - 1, -alpha, true, search)?;
+            if reduced <= alpha { score = Some(reduced); }
+        }
+        let score = match score {
+            Some(score) => score,
+            None => pvs(&game_copy, child.as_ref(), n == 0, depth - 1, ply + 1, alpha, beta, search)?,
+        };
+
+        if score > best {
+            best = score;
+            best_index = i;
+        }
+        alpha = alpha.max(score);
+        if alpha >= beta {
+            break;
+        }
+    }
+
+    let bound = if best >= beta {
+        Bound::Lower
+    } else if best <= alpha_start {
+        Bound::Upper
+    } else {
+        Bound::Exact
+    };
+    search.tt.store(key, Entry { score: to_table(best, ply), depth, bound, best: best_index as u8 });
+
+    Ok(best)
+}
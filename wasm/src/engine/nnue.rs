@@ -0,0 +1,243 @@
+use crate::game::{
+    Game,
+    board::{Bits, index},
+    pieces::{Color, KINDS},
+};
+
+use super::EngineError;
+
+/* An efficiently updatable network, 768 inputs for each piece on
+ * each square, a hidden layer of N clipped ReLUs seen from both
+ * sides and a single output. https://www.chessprogramming.org/NNUE
+ *
+ * The file, all little-endian
+ *     magic            "CWNN"
+ *     version          u32, 1
+ *     hidden           u32, N, a multiple of 8
+ *     feature weights  i16 × 768 × N, the N weights of each input in turn
+ *     feature biases   i16 × N
+ *     output weights   i16 × 2N, the side to move's half first
+ *     output bias      i32
+ *
+ * An input is color × 384 + kind × 64 + square for the side the
+ * accumulator belongs to, with black seeing the board flipped and
+ * its own pieces as color 0. Kinds go in the order of KINDS
+ */
+const MAGIC: &[u8; 4] = b"CWNN";
+const VERSION: u32 = 1;
+const INPUTS: usize = 768;
+
+// quantisation of the hidden layer and the output, and centipawns per unit
+const QA: i32 = 255;
+const QB: i32 = 64;
+const SCALE: i32 = 400;
+
+// the most a network may score in centipawns, far below a mate
+const MAX_CENTIPAWNS: i64 = 100_000;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Network {
+    hidden: usize,
+    feature_weights: Vec<i16>,
+    feature_biases: Vec<i16>,
+    output_weights: Vec<i16>,
+    output_bias: i32,
+}
+
+/* The hidden layer before activation, white's half then black's.
+ * The search copies positions instead of unmaking moves, so a
+ * child's accumulator is its parent's updated by the pieces
+ * the move took away and put down
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct Accumulator {
+    values: Vec<i16>,
+}
+
+fn network_error(message: &str) -> EngineError {
+    EngineError::Network(message.to_string())
+}
+
+impl Network {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, EngineError> {
+        let mut reader = bytes;
+        let mut take = |n: usize| -> Result<&[u8], EngineError> {
+            if reader.len() < n { return Err(network_error("file is too short")); }
+            let (head, rest) = reader.split_at(n);
+            reader = rest;
+            Ok(head)
+        };
+        let u32_at = |b: &[u8]| u32::from_le_bytes([b[0], b[1], b[2], b[3]]);
+
+        if take(4)? != MAGIC {
+            return Err(network_error("not a network file"));
+        }
+        if u32_at(take(4)?) != VERSION {
+            return Err(network_error("unknown version"));
+        }
+        let hidden = u32_at(take(4)?) as usize;
+        if hidden == 0 || !hidden.is_multiple_of(8) || hidden > 1 << 16 {
+            return Err(network_error("hidden layer must be a multiple of 8"));
+        }
+
+        let i16s = |b: &[u8]| b.chunks_exact(2).map(|c| i16::from_le_bytes([c[0], c[1]])).collect::<Vec<_>>();
+        let feature_weights = i16s(take(INPUTS * hidden * 2)?);
+        let feature_biases = i16s(take(hidden * 2)?);
+        let output_weights = i16s(take(hidden * 4)?);
+        let output_bias = u32_at(take(4)?) as i32;
+        if take(1).is_ok() {
+            return Err(network_error("file is too long"));
+        }
+
+        Ok(Network { hidden, feature_weights, feature_biases, output_weights, output_bias })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(16 + (INPUTS + 3) * self.hidden * 2);
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&VERSION.to_le_bytes());
+        bytes.extend_from_slice(&(self.hidden as u32).to_le_bytes());
+        for w in self.feature_weights.iter().chain(&self.feature_biases).chain(&self.output_weights) {
+            bytes.extend_from_slice(&w.to_le_bytes());
+        }
+        bytes.extend_from_slice(&self.output_bias.to_le_bytes());
+        bytes
+    }
+
+    pub fn hidden(&self) -> usize {
+        self.hidden
+    }
+
+    // the input of a piece for white's half and for black's
+    fn inputs(color: Color, kind: usize, square: usize) -> [usize; 2] {
+        let (ours, theirs) = match color {
+            Color::White => (0, 384),
+            Color::Black => (384, 0),
+        };
+        [ours + kind * 64 + square, theirs + kind * 64 + (square ^ 56)]
+    }
+
+    fn weights(&self, input: usize) -> &[i16] {
+        &self.feature_weights[input * self.hidden..(input + 1) * self.hidden]
+    }
+
+    // every piece added to the biases
+    pub fn refresh(&self, game: &Game) -> Accumulator {
+        let mut values = [self.feature_biases.as_slice(), &self.feature_biases].concat();
+        for color in [Color::White, Color::Black] {
+            for (kind, k) in KINDS.iter().enumerate() {
+                for bit in Bits(game.board.get(*k, color)) {
+                    self.apply(&mut values, Network::inputs(color, kind, index(bit)), add);
+                }
+            }
+        }
+        Accumulator { values }
+    }
+
+    /* The accumulator of child from its parent's, any move
+     * changes a few squares so castling, en passant and
+     * promotions need nothing of their own
+     */
+    pub fn update(&self, accumulator: &Accumulator, parent: &Game, child: &Game) -> Accumulator {
+        let mut values = accumulator.values.clone();
+        for color in [Color::White, Color::Black] {
+            for (kind, k) in KINDS.iter().enumerate() {
+                let (before, after) = (parent.board.get(*k, color), child.board.get(*k, color));
+                for bit in Bits(before & !after) {
+                    self.apply(&mut values, Network::inputs(color, kind, index(bit)), sub);
+                }
+                for bit in Bits(after & !before) {
+                    self.apply(&mut values, Network::inputs(color, kind, index(bit)), add);
+                }
+            }
+        }
+        Accumulator { values }
+    }
+
+    #[inline]
+    fn apply(&self, values: &mut [i16], inputs: [usize; 2], op: fn(&mut [i16], &[i16])) {
+        let (white, black) = values.split_at_mut(self.hidden);
+        op(white, self.weights(inputs[0]));
+        op(black, self.weights(inputs[1]));
+    }
+
+    // centipawns for the side to move
+    pub fn evaluate(&self, accumulator: &Accumulator, turn: Color) -> i32 {
+        let (white, black) = accumulator.values.split_at(self.hidden);
+        let (us, them) = match turn {
+            Color::White => (white, black),
+            Color::Black => (black, white),
+        };
+        let (w_us, w_them) = self.output_weights.split_at(self.hidden);
+        // 2N products of up to QA × i16::MAX overflow an i32 long before N's limit
+        let sum = dot(us, w_us) + dot(them, w_them) + self.output_bias as i64;
+        (sum * SCALE as i64 / (QA * QB) as i64).clamp(-MAX_CENTIPAWNS, MAX_CENTIPAWNS) as i32
+    }
+}
+
+/* wasm's simd128 works on 8 lanes at once when the build enables
+ * it, RUSTFLAGS='-C target-feature=+simd128'. Elsewhere the plain
+ * loops are left for the compiler to vectorise
+ */
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+mod simd {
+    use core::arch::wasm32::*;
+
+    pub fn add(values: &mut [i16], weights: &[i16]) {
+        for (v, w) in values.chunks_exact_mut(8).zip(weights.chunks_exact(8)) {
+            // SAFETY: both chunks are 8 i16s, 16 bytes, wasm loads need no alignment
+            unsafe {
+                let sum = i16x8_add(v128_load(v.as_ptr() as *const v128), v128_load(w.as_ptr() as *const v128));
+                v128_store(v.as_mut_ptr() as *mut v128, sum);
+            }
+        }
+    }
+
+    pub fn sub(values: &mut [i16], weights: &[i16]) {
+        for (v, w) in values.chunks_exact_mut(8).zip(weights.chunks_exact(8)) {
+            // SAFETY: as in add
+            unsafe {
+                let difference = i16x8_sub(v128_load(v.as_ptr() as *const v128), v128_load(w.as_ptr() as *const v128));
+                v128_store(v.as_mut_ptr() as *mut v128, difference);
+            }
+        }
+    }
+
+    // each chunk's pairs fit an i32 lane, the running sum is widened to i64 lanes
+    pub fn dot(values: &[i16], weights: &[i16]) -> i64 {
+        let (zero, qa) = (i16x8_splat(0), i16x8_splat(super::QA as i16));
+        let mut sum = i64x2_splat(0);
+        for (v, w) in values.chunks_exact(8).zip(weights.chunks_exact(8)) {
+            // SAFETY: as in add
+            unsafe {
+                let active = i16x8_min(i16x8_max(v128_load(v.as_ptr() as *const v128), zero), qa);
+                let pairs = i32x4_dot_i16x8(active, v128_load(w.as_ptr() as *const v128));
+                sum = i64x2_add(sum, i64x2_add(i64x2_extend_low_i32x4(pairs), i64x2_extend_high_i32x4(pairs)));
+            }
+        }
+        i64x2_extract_lane::<0>(sum) + i64x2_extract_lane::<1>(sum)
+    }
+}
+
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+use simd::{add, dot, sub};
+
+#[cfg(not(all(target_arch = "wasm32", target_feature = "simd128")))]
+fn add(values: &mut [i16], weights: &[i16]) {
+    for (v, w) in values.iter_mut().zip(weights) {
+        *v = v.wrapping_add(*w);
+    }
+}
+
+#[cfg(not(all(target_arch = "wasm32", target_feature = "simd128")))]
+fn sub(values: &mut [i16], weights: &[i16]) {
+    for (v, w) in values.iter_mut().zip(weights) {
+        *v = v.wrapping_sub(*w);
+    }
+}
+
+// clipped ReLU of each value times its weight
+#[cfg(not(all(target_arch = "wasm32", target_feature = "simd128")))]
+fn dot(values: &[i16], weights: &[i16]) -> i64 {
+    values.iter().zip(weights).map(|(v, w)| ((*v as i32).clamp(0, QA) * *w as i32) as i64).sum()
+}
@@ -0,0 +1,42 @@
+use std::sync::{Arc, atomic::{AtomicBool, Ordering::Relaxed}};
+
+/* Signals to a search running in the background. A search of the
+ * expected reply ponders, ignoring the clock and going as deep as
+ * it can, until the reply is played and hit makes it the real
+ * search with the time spent pondering counted, or a miss stops
+ * it and its move is thrown away. Any search given one can be stopped
+ */
+#[derive(Debug, Default)]
+pub struct Ponder {
+    pondering: AtomicBool,
+    stop: Arc<AtomicBool>,
+}
+
+impl Ponder {
+    pub fn new(pondering: bool) -> Self {
+        Ponder { pondering: AtomicBool::new(pondering), stop: Arc::new(AtomicBool::new(false)) }
+    }
+
+    pub fn hit(&self) {
+        self.pondering.store(false, Relaxed);
+    }
+
+    pub fn stop(&self) {
+        self.stop.store(true, Relaxed);
+    }
+
+    #[inline]
+    pub fn is_pondering(&self) -> bool {
+        self.pondering.load(Relaxed)
+    }
+
+    #[inline]
+    pub fn is_stopped(&self) -> bool {
+        self.stop.load(Relaxed)
+    }
+
+    // the stop alone, for a search that never ponders
+    pub fn stop_flag(&self) -> Arc<AtomicBool> {
+        self.stop.clone()
+    }
+}
@@ -0,0 +1,54 @@
+use std::fmt;
+
+use super::{evaluate::PAWN_VAL, negamax::{MATE, MATE_BOUND}};
+
+// centipawns in a unit of evaluation
+const CENTIPAWNS: i32 = 100 / PAWN_VAL;
+
+pub fn centipawns(eval: i32) -> i32 {
+    eval * CENTIPAWNS
+}
+
+// to the nearest unit of evaluation, halves away from zero so a sign flip scores the same
+pub fn from_centipawns(cp: i32) -> i32 {
+    (cp + CENTIPAWNS / 2 * cp.signum()) / CENTIPAWNS
+}
+
+/* A search score from the side to move's view, mates
+ * are counted in moves and negative when being mated
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Score {
+    Centipawns(i32),
+    Mate(i32),
+}
+
+impl Score {
+    pub fn from_eval(score: i32) -> Self {
+        if score >= MATE_BOUND {
+            // the mating move is the last of an odd number of plies
+            Score::Mate((MATE - score + 1) / 2)
+        } else if score <= -MATE_BOUND {
+            Score::Mate(-(MATE + score) / 2)
+        } else {
+            Score::Centipawns(centipawns(score))
+        }
+    }
+
+    pub fn mate(&self) -> Option<i32> {
+        match self {
+            Score::Mate(n) => Some(*n),
+            Score::Centipawns(_) => None,
+        }
+    }
+}
+
+// as in a UCI info line, 'cp 150' or 'mate -2'
+impl fmt::Display for Score {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Score::Centipawns(cp) => write!(f, "cp {}", cp),
+            Score::Mate(n) => write!(f, "mate {}", n),
+        }
+    }
+}
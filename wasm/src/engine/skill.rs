@@ -0,0 +1,85 @@
+use super::Score;
+
+pub const MAX_LEVEL: u8 = 20;
+
+// the ratings UCI_Elo accepts, mapped evenly onto the levels
+pub const MIN_ELO: u32 = 600;
+pub const MAX_ELO: u32 = 2400;
+
+/* Playing strength from 0 to 20. Below 20 the engine searches
+ * shallower and fewer nodes, blurs its evaluation and picks
+ * from its few best moves, the weaker the more likely
+ * a move that is not the best
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Skill {
+    level: u8,
+}
+
+impl Skill {
+    pub fn new(level: u8) -> Self {
+        Skill { level: level.min(MAX_LEVEL) }
+    }
+
+    // a rough guide, the levels have not been rated against other engines
+    pub fn from_elo(elo: u32) -> Self {
+        let elo = elo.clamp(MIN_ELO, MAX_ELO) - MIN_ELO;
+        Skill::new((elo * MAX_LEVEL as u32 / (MAX_ELO - MIN_ELO)) as u8)
+    }
+
+    pub fn level(&self) -> u8 {
+        self.level
+    }
+
+    fn full(&self) -> bool {
+        self.level == MAX_LEVEL
+    }
+
+    pub fn depth(&self) -> Option<u8> {
+        if self.full() { None } else { Some(1 + self.level / 3) }
+    }
+
+    pub fn nodes(&self) -> Option<u64> {
+        if self.full() { None } else { Some(100 << (self.level / 2)) }
+    }
+
+    // the most added to or taken off an evaluation, a pawn is 2
+    pub fn noise(&self) -> i32 {
+        (MAX_LEVEL - self.level) as i32 / 5
+    }
+
+    // moves searched exactly to choose from
+    pub fn multipv(&self) -> usize {
+        if self.full() { 1 } else { 4 }
+    }
+
+    /* Picks one of the lines, sorted best first. Each is pushed
+     * up by part of its distance from the best and a random amount
+     * up to the spread of the lines, the weakness sets how much
+     */
+    pub fn pick(&self, lines: &[(usize, i32)], seed: u64) -> usize {
+        if self.full() || lines.len() < 2 { return 0; }
+
+        // centipawns, mates counted as ten pawns
+        let cp = |score: i32| match Score::from_eval(score) {
+            Score::Centipawns(cp) => cp.clamp(-1_000, 1_000) as i64,
+            Score::Mate(n) => n.signum() as i64 * 1_000,
+        };
+        let top = cp(lines[0].1);
+        let spread = (top - cp(lines[lines.len() - 1].1)).min(100); // at most a pawn
+        let weakness = 120 - 2 * self.level as i64;
+
+        let mut random = seed | 1;
+        let mut best = (0, i64::MIN);
+        for (i, (_, score)) in lines.iter().enumerate() {
+            random ^= random << 13;
+            random ^= random >> 7;
+            random ^= random << 17;
+            let push = (weakness * (top - cp(*score)) + spread * (random % weakness as u64) as i64) / 128;
+            if cp(*score) + push >= best.1 {
+                best = (i, cp(*score) + push);
+            }
+        }
+        best.0
+    }
+}
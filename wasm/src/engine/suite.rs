@@ -0,0 +1,69 @@
+use std::fmt;
+
+use super::{Engine, SearchLimits};
+use crate::game::{
+    epd::Epd,
+    notation::to_san,
+};
+
+pub struct SuiteResult {
+    pub id: String,
+    pub fen: String,
+    pub played: Option<String>,
+    pub solved: bool,
+}
+
+/* Outcome of running the engine over an EPD test suite,
+ * a position is solved when the move played is one of
+ * its 'bm' moves and none of its 'am' moves
+ */
+pub struct SuiteReport {
+    pub results: Vec<SuiteResult>,
+    pub solved: usize,
+    pub failed: usize,
+}
+
+// compare SAN ignoring check and annotation suffixes
+fn same_move(a: &str, b: &str) -> bool {
+    let strip = |s: &str| s.trim_end_matches(['+', '#', '!', '?']).replace('0', "O");
+    strip(a) == strip(b)
+}
+
+pub fn run_suite(suite: &[Epd], limits: &SearchLimits) -> SuiteReport {
+    let mut report = SuiteReport { results: Vec::with_capacity(suite.len()), solved: 0, failed: 0 };
+
+    for (i, epd) in suite.iter().enumerate() {
+        let played = Engine::search(&epd.game, limits)
+            .ok()
+            .and_then(|mv| to_san(&epd.game, &mv).ok());
+
+        let solved = match &played {
+            Some(san) => {
+                (epd.best_moves.is_empty() || epd.best_moves.iter().any(|m| same_move(m, san))) &&
+                !epd.avoid_moves.iter().any(|m| same_move(m, san))
+            },
+            None => false,
+        };
+
+        if solved { report.solved += 1; } else { report.failed += 1; }
+        report.results.push(SuiteResult {
+            id: epd.id.clone().unwrap_or_else(|| format!("#{}", i + 1)),
+            fen: epd.fen.clone(),
+            played,
+            solved,
+        });
+    }
+
+    report
+}
+
+impl fmt::Display for SuiteReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for r in &self.results {
+            let status = if r.solved { "solved" } else { "failed" };
+            let played = r.played.as_deref().unwrap_or("-");
+            writeln!(f, "{:<24} {:<8} {:<8} {}", r.id, status, played, r.fen)?;
+        }
+        write!(f, "solved: {}/{}, failed: {}", self.solved, self.results.len(), self.failed)
+    }
+}
@@ -0,0 +1,128 @@
+use std::time::Duration;
+use web_time::Instant;
+
+use super::evaluate::PAWN_VAL;
+
+// kept back on every move for the GUI and the transport
+const OVERHEAD: Duration = Duration::from_millis(30);
+
+// moves a sudden death clock is shared over, and the most of a moves to go
+const SUDDEN_DEATH_MOVES: u32 = 30;
+
+// how far past the soft limit the hard one lets an iteration run
+const HARD_SCALE: u32 = 4;
+
+// the most of the clock one move may take, so lag cannot lose on time
+const MAX_SHARE: u32 = 2;
+
+/* The side to move's clock. The increment comes back after the
+ * move, Fischer, and the delay is free time before the clock runs
+ * down, which a Bronstein delay gives back after the move instead.
+ * Moves to go is None for sudden death
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TimeControl {
+    pub time: Duration,
+    pub increment: Duration,
+    pub delay: Duration,
+    pub moves_to_go: Option<u32>,
+}
+
+/* Allots a move a soft limit, checked between depths, and a hard
+ * limit the search is stopped at. The soft limit stretches when
+ * the best move keeps changing or the score falls and shrinks
+ * when the move holds, an easy move stops the search early
+ */
+#[derive(Clone, Debug)]
+pub struct TimeManager {
+    start: Instant,
+    soft: Duration,
+    hard: Duration,
+    budget: Duration,
+    last: Option<(usize, i32)>,
+    stability: u32,
+    easy: bool,
+}
+
+impl TimeManager {
+    pub fn new(control: &TimeControl) -> Self {
+        let left = control.time.saturating_sub(OVERHEAD);
+        let moves = control.moves_to_go.map_or(SUDDEN_DEATH_MOVES, |m| m.clamp(1, SUDDEN_DEATH_MOVES));
+
+        // never more of the clock than the moves left could spare
+        let ceiling = (left * 4 / (moves + 3)).min(left / MAX_SHARE);
+        let soft = (left / moves + control.increment * 3 / 4).min(ceiling);
+        let hard = (soft * HARD_SCALE).min(ceiling);
+
+        TimeManager {
+            start: Instant::now(),
+            soft: soft + control.delay,
+            hard: hard + control.delay,
+            budget: soft + control.delay,
+            last: None,
+            stability: 0,
+            easy: false,
+        }
+    }
+
+    pub fn soft(&self) -> Duration {
+        self.soft
+    }
+
+    pub fn hard(&self) -> Duration {
+        self.hard
+    }
+
+    // the soft limit as stretched or shrunk after the last depth
+    pub fn budget(&self) -> Duration {
+        self.budget
+    }
+
+    pub fn deadline(&self) -> Instant {
+        self.start + self.hard
+    }
+
+    // only one move to play, a depth is enough for its score
+    pub fn single_reply(&mut self) {
+        self.soft = Duration::ZERO;
+    }
+
+    // nothing else comes close, a recapture most often
+    pub fn easy_move(&mut self) {
+        self.easy = true;
+    }
+
+    /* Told the best move's index and score after each completed
+     * depth, whether another depth is worth starting
+     */
+    pub fn next_depth(&mut self, best: usize, score: i32) -> bool {
+        let mut percent = match self.last {
+            None => 100,
+            Some((last, _)) if last != best => {
+                self.stability = 0;
+                160
+            },
+            Some(_) => {
+                self.stability += 1;
+                match self.stability {
+                    1 => 120,
+                    2..=3 => 100,
+                    _ => 70,
+                }
+            },
+        };
+
+        // a falling score is trouble coming into view, half again a pawn up to two
+        if let Some((_, last)) = self.last {
+            let drop = last.saturating_sub(score).clamp(0, 2 * PAWN_VAL);
+            percent += drop * 50 / PAWN_VAL;
+        }
+        if self.easy {
+            percent = 25;
+        }
+        self.last = Some((best, score));
+
+        self.budget = (self.soft * percent as u32 / 100).min(self.hard);
+        self.start.elapsed() < self.budget
+    }
+}
@@ -0,0 +1,72 @@
+use std::sync::atomic::{AtomicU64, Ordering::Relaxed};
+
+/* Scores are kept from the view of the side to move, a bound
+ * marks a score cut off by alpha or beta as only a limit
+ */
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Bound {
+    Exact,
+    Lower, // the score is at least this
+    Upper, // the score is at most this
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Entry {
+    pub score: i32,
+    pub depth: u8,
+    pub bound: Bound,
+    pub best: u8, // index of the best move in the move list
+}
+
+impl Entry {
+    fn pack(&self) -> u64 {
+        let bound = match self.bound {
+            Bound::Exact => 0,
+            Bound::Lower => 1,
+            Bound::Upper => 2,
+        };
+        self.score as u32 as u64 | (self.depth as u64) << 32 | bound << 40 | (self.best as u64) << 48
+    }
+
+    fn unpack(data: u64) -> Self {
+        let bound = match data >> 40 & 0x3 {
+            0 => Bound::Exact,
+            1 => Bound::Lower,
+            _ => Bound::Upper,
+        };
+        Entry { score: data as u32 as i32, depth: (data >> 32) as u8, bound, best: (data >> 48) as u8 }
+    }
+}
+
+/* Shared by every search thread without locks, each slot holds
+ * its key xored with its data so a torn write from another
+ * thread reads back as a miss instead of a wrong entry
+ */
+pub struct TranspositionTable {
+    entries: Vec<(AtomicU64, AtomicU64)>,
+}
+
+impl TranspositionTable {
+    pub fn new(megabytes: usize) -> Self {
+        let len = ((megabytes << 20) / 16).max(1);
+        TranspositionTable { entries: (0..len).map(|_| (AtomicU64::new(0), AtomicU64::new(0))).collect() }
+    }
+
+    #[inline]
+    pub fn probe(&self, key: u64) -> Option<Entry> {
+        let (check, data) = &self.entries[key as usize % self.entries.len()];
+        let (check, data) = (check.load(Relaxed), data.load(Relaxed));
+        if check ^ data != key || data == 0 { return None; }
+        Some(Entry::unpack(data))
+    }
+
+    // a shallower result never replaces a deeper one of the same position
+    #[inline]
+    pub fn store(&self, key: u64, entry: Entry) {
+        if self.probe(key).is_some_and(|e| e.depth > entry.depth) { return; }
+        let (check, data) = &self.entries[key as usize % self.entries.len()];
+        let value = entry.pack();
+        check.store(key ^ value, Relaxed);
+        data.store(value, Relaxed);
+    }
+}
@@ -0,0 +1,335 @@
+use std::{
+    error::Error,
+    fmt,
+};
+use rayon::prelude::*;
+
+use crate::{
+    error::ErrorCode,
+    game::{
+        Game,
+        epd::{self, EpdError},
+        fen::{self, FenError},
+        notation::{from_san, NotationError},
+        pieces::{Color, Piece},
+        util::GameInfo,
+    },
+};
+use super::{
+    EngineConfig,
+    evaluate::{evaluate_with, Weights, PAWN_VAL},
+};
+
+// plies of a game left out, the opening says little about the result
+const OPENING_PLIES: usize = 8;
+
+// captures followed past this are cut off
+const QUIESCENCE_PLIES: u8 = 16;
+
+/* Texel tuning, https://www.chessprogramming.org/Texel%27s_Tuning_Method
+ * Each position is labelled with the result of its game and the
+ * weights are nudged one at a time while that lowers the mean
+ * squared error of the evaluation's predicted result
+ */
+
+// a position and its game's result for white, 1 a win and 0.5 a draw
+pub struct Sample {
+    pub game: Game,
+    pub result: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TuneError {
+    MissingResult,
+    Epd(EpdError),
+    Fen(FenError),
+    Notation(NotationError),
+}
+
+impl fmt::Display for TuneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Tune Error: ")?;
+        match self {
+            TuneError::MissingResult => write!(f, "position has no game result"),
+            TuneError::Epd(e) => write!(f, "{}", e),
+            TuneError::Fen(e) => write!(f, "{}", e),
+            TuneError::Notation(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for TuneError {}
+
+impl ErrorCode for TuneError {
+    fn code(&self) -> &'static str {
+        match self {
+            TuneError::MissingResult => "TUNE_MISSING_RESULT",
+            TuneError::Epd(e) => e.code(),
+            TuneError::Fen(e) => e.code(),
+            TuneError::Notation(e) => e.code(),
+        }
+    }
+}
+
+impl From<EpdError> for TuneError {
+    fn from(e: EpdError) -> Self {
+        TuneError::Epd(e)
+    }
+}
+
+impl From<FenError> for TuneError {
+    fn from(e: FenError) -> Self {
+        TuneError::Fen(e)
+    }
+}
+
+impl From<NotationError> for TuneError {
+    fn from(e: NotationError) -> Self {
+        TuneError::Notation(e)
+    }
+}
+
+// "1-0", "0-1" and "1/2-1/2" or "[1.0]", "[0.0]" and "[0.5]"
+fn result(token: &str) -> Option<f64> {
+    match token.trim_matches(|c| matches!(c, '"' | '[' | ']' | ';')) {
+        "1-0" | "1.0" => Some(1.0),
+        "0-1" | "0.0" => Some(0.0),
+        "1/2-1/2" | "0.5" => Some(0.5),
+        _ => None,
+    }
+}
+
+/* A line of an EPD with the result in a 'c9' operation, or
+ * a FEN followed by the result as most tuning sets have it
+ */
+pub fn decode_line(line: &str) -> Result<Sample, TuneError> {
+    if let Ok(epd) = epd::decode(line) {
+        let c9 = epd.operations.iter().find(|(op, _)| op == "c9").and_then(|(_, o)| o.first());
+        if let Some(result) = c9.and_then(|r| result(r)) {
+            return Ok(Sample { game: epd.game, result });
+        }
+    }
+
+    let line = line.trim();
+    let (fen, token) = line.rsplit_once(char::is_whitespace).ok_or(TuneError::MissingResult)?;
+    let result = result(token).ok_or(TuneError::MissingResult)?;
+    Ok(Sample { game: fen::decode(fen.trim().trim_end_matches(';'))?, result })
+}
+
+/* Every position of every finished game after the opening.
+ * Comments, variations and annotations are skipped. A game that
+ * cannot be read is left out whole and its error returned with
+ * its number, counted from 1, so one bad game costs only itself
+ */
+pub fn decode_pgn(pgn: &str) -> (Vec<Sample>, Vec<(usize, TuneError)>) {
+    const START: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+    let (mut samples, mut errors) = (Vec::new(), Vec::new());
+
+    let mut start = String::from(START);
+    let mut outcome = None;
+    let mut moves = String::new();
+    let mut number = 1;
+
+    let read = |start: &str, result: f64, moves: &str| -> Result<Vec<Sample>, TuneError> {
+        let mut game = fen::decode(start)?;
+        let mut samples = Vec::new();
+        for (ply, san) in tokens(moves).into_iter().enumerate() {
+            let mv = from_san(&game, &san)?;
+            game.move_piece(*mv.bits());
+            if ply >= OPENING_PLIES {
+                samples.push(Sample { game: game.clone(), result });
+            }
+        }
+        Ok(samples)
+    };
+    let mut finish = |number: usize, start: &str, outcome: Option<f64>, moves: &str| {
+        let Some(result) = outcome else { return };
+        match read(start, result, moves) {
+            Ok(game) => samples.extend(game),
+            Err(e) => errors.push((number, e)),
+        }
+    };
+
+    for line in pgn.lines() {
+        let line = line.trim();
+        if let Some(tag) = line.strip_prefix('[') {
+            // a tag after movetext starts the next game
+            if !moves.trim().is_empty() {
+                finish(number, &start, outcome, &moves);
+                (start, outcome) = (String::from(START), None);
+                moves.clear();
+                number += 1;
+            }
+            let (name, value) = tag.trim_end_matches(']').split_once(' ').unwrap_or((tag, ""));
+            let value = value.trim_matches('"');
+            match name {
+                "FEN" => start = value.to_string(),
+                "Result" => outcome = result(value),
+                _ => (),
+            }
+        } else {
+            moves.push_str(line);
+            moves.push('\n');
+        }
+    }
+    finish(number, &start, outcome, &moves);
+
+    (samples, errors)
+}
+
+// the SAN moves of a game's movetext
+fn tokens(movetext: &str) -> Vec<String> {
+    let mut text = String::with_capacity(movetext.len());
+    let (mut braces, mut parens, mut line_comment) = (false, 0, false);
+    for c in movetext.chars() {
+        match c {
+            '\n' => { line_comment = false; text.push(' '); },
+            _ if line_comment => (),
+            '{' => braces = true,
+            '}' => braces = false,
+            _ if braces => (),
+            ';' => line_comment = true,
+            '(' => parens += 1,
+            ')' => parens -= 1,
+            _ if parens > 0 => (),
+            _ => text.push(c),
+        }
+    }
+
+    text.split_whitespace()
+        .filter(|t| result(t).is_none() && *t != "*")
+        .map(move_number)
+        .filter(|t| !t.is_empty() && !t.starts_with('$'))
+        .map(|t| t.trim_end_matches(['!', '?']).to_string())
+        .collect()
+}
+
+// a token without its move number, '12.' or '12...', castling with zeros kept whole
+fn move_number(token: &str) -> &str {
+    let digits = token.trim_start_matches(|c: char| c.is_ascii_digit());
+    let rest = digits.trim_start_matches('.');
+    if digits.len() < token.len() && rest.len() < digits.len() { rest } else { token }
+}
+
+/* Follows captures until the position is quiet, with the
+ * evaluation standing in for the side to move declining them.
+ * Returns the score and the quiet position at the end of the line
+ */
+pub fn quiesce(game: &Game, mut alpha: i32, beta: i32, ply: u8, weights: &Weights) -> (i32, Game) {
+    let Ok((moves, info)) = game.moves_verbose() else { return (0, game.clone()) };
+    let stand = evaluate_with(game, &info, &EngineConfig::default(), weights);
+    if moves.is_empty() || stand >= beta || ply >= QUIESCENCE_PLIES {
+        return (stand, game.clone());
+    }
+    alpha = alpha.max(stand);
+
+    let mut leaf = game.clone();
+    for mv in moves.iter().filter(|m| m.bits() & info.opp_pieces != 0) {
+        let mut next = game.clone();
+        next.move_piece(*mv.bits());
+        let (score, quiet) = quiesce(&next, -beta, -alpha, ply + 1, weights);
+        if -score > alpha {
+            (alpha, leaf) = (-score, quiet);
+            if alpha >= beta { break; }
+        }
+    }
+    (alpha, leaf)
+}
+
+/* The quiet positions to tune on, each with the info its evaluation
+ * needs. Positions without a move to play are left out
+ */
+pub struct Tuner {
+    positions: Vec<(Game, GameInfo, f64)>,
+}
+
+impl Tuner {
+    pub fn new(samples: Vec<Sample>, weights: &Weights) -> Self {
+        let positions = samples.into_par_iter().filter_map(|s| {
+            let (_, quiet) = quiesce(&s.game, -i32::MAX, i32::MAX, 0, weights);
+            let (moves, info) = quiet.moves_verbose().ok()?;
+            if moves.is_empty() { return None; }
+            Some((quiet, info, s.result))
+        }).collect();
+        Tuner { positions }
+    }
+
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    // mean squared error of the results predicted from the evaluation
+    pub fn error(&self, weights: &Weights, k: f64) -> f64 {
+        let config = EngineConfig::default();
+        let sum: f64 = self.positions.par_iter().map(|(game, info, result)| {
+            let mut eval = evaluate_with(game, info, &config, weights);
+            if game.turn == Color::Black { eval = -eval; }
+            let centipawns = (eval * 100 / PAWN_VAL) as f64;
+            let predicted = 1.0 / (1.0 + 10f64.powf(-k * centipawns / 400.0));
+            (result - predicted).powi(2)
+        }).sum();
+        sum / self.positions.len().max(1) as f64
+    }
+
+    // the scaling that best fits the weights as they are
+    pub fn fit_k(&self, weights: &Weights) -> f64 {
+        let mut best = (1.0, self.error(weights, 1.0));
+        let mut step = 0.1;
+        for _ in 0..3 {
+            let centre = best.0;
+            for i in -10..=10 {
+                let k = centre + i as f64 * step;
+                if k <= 0.0 { continue; }
+                let e = self.error(weights, k);
+                if e < best.1 { best = (k, e); }
+            }
+            step /= 10.0;
+        }
+        best.0
+    }
+
+    /* Steps each weight up and down by one, keeping any step
+     * that lowers the error, until no step does. Pass is told
+     * after each round of the weights and error so far
+     */
+    pub fn tune(&self, weights: &Weights, k: f64, mut pass: impl FnMut(usize, &Weights, f64)) -> Weights {
+        let mut best = weights.to_array();
+        let mut error = self.error(weights, k);
+
+        for round in 1.. {
+            let mut improved = false;
+            for i in 0..best.len() {
+                for step in [1, -1] {
+                    let mut next = best;
+                    next[i] += step;
+                    let e = self.error(&Weights::from_array(next), k);
+                    if e < error {
+                        (best, error, improved) = (next, e, true);
+                        break;
+                    }
+                }
+            }
+            pass(round, &Weights::from_array(best), error);
+            if !improved { break; }
+        }
+        Weights::from_array(best)
+    }
+}
+
+// the source of weights.rs, the note goes in its header
+pub fn to_rust(weights: &Weights, note: &str) -> String {
+    let mut source = String::from("/* Evaluation weights, written by the tune binary\n");
+    source += " * cargo run --profile perft --bin tune -- [FILE]...\n";
+    source += &format!(" * {}\n */\n\n", note);
+    source += "use super::evaluate::Weights;\n\n";
+    source += "pub const WEIGHTS: Weights = Weights {\n";
+    for (name, value) in Weights::NAMES.iter().zip(weights.to_array()) {
+        source += &format!("    {}: {},\n", name, value);
+    }
+    source += "};\n";
+    source
+}
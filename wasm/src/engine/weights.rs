@@ -0,0 +1,22 @@
+/* Evaluation weights, written by the tune binary
+ * cargo run --profile perft --bin tune -- [FILE]...
+ * picked by hand, not tuned yet
+ */
+
+use super::evaluate::Weights;
+
+pub const WEIGHTS: Weights = Weights {
+    bishop: 6,
+    knight: 6,
+    rook: 10,
+    queen: 18,
+    hot_zone: 1,
+    king_safety: 2,
+    check: 2,
+    double_check: 16,
+    check_few_moves: 16,
+    few_moves: 5,
+    doubled: 0,
+    isolated: 0,
+    passed: 0,
+};
@@ -0,0 +1,7 @@
+/* Stable identifiers for error variants, passed to JS
+ * as the `code` property of thrown errors so the UI can
+ * react to a failure without parsing its message
+ */
+pub trait ErrorCode {
+    fn code(&self) -> &'static str;
+}
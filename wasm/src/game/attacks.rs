@@ -0,0 +1,233 @@
+use std::sync::OnceLock;
+
+use super::{
+    board::*,
+    pieces::Color,
+};
+
+/* Attack tables, the leapers are built at compile time
+ * and the sliders on first use. Sliders are looked up
+ * with magic bit boards, or with PEXT when building
+ * natively with BMI2 (RUSTFLAGS="-C target-feature=+bmi2")
+ */
+
+const fn knight_attacks(b: u64) -> u64 {
+    let one = east(b) | west(b);
+    let two = east(east(b)) | west(west(b));
+    one << 16 | one >> 16 | two << 8 | two >> 8
+}
+
+const fn king_attacks(b: u64) -> u64 {
+    let row = b | east(b) | west(b);
+    (row | north(row) | south(row)) & !b
+}
+
+// 0 knight, 1 king, 2 white pawn, 3 black pawn
+const fn table(leaper: u8) -> [u64; 64] {
+    let mut t = [0; 64];
+    let mut i = 0;
+    while i < 64 {
+        let b = 1 << i;
+        t[i] = match leaper {
+            0 => knight_attacks(b),
+            1 => king_attacks(b),
+            2 => north_east(b) | north_west(b),
+            _ => south_east(b) | south_west(b),
+        };
+        i += 1;
+    }
+    t
+}
+
+const KNIGHT: [u64; 64] = table(0);
+const KING: [u64; 64] = table(1);
+const PAWN: [[u64; 64]; 2] = [table(2), table(3)];
+
+const ROOK_STEPS: [fn(u64) -> u64; 4] = [north, south, east, west];
+const BISHOP_STEPS: [fn(u64) -> u64; 4] = [north_east, north_west, south_east, south_west];
+
+// found by trial, each maps every blocker set of its
+// square to a table index without a harmful collision
+const ROOK_MAGICS: [u64; 64] = [
+    0x1080004008801020, 0x0840092002c03000, 0x1900200010400900, 0x0880100008000480,
+    0x4200100420080200, 0x8100020100080400, 0x0200040110886200, 0x0200008040220411,
+    0x0404800084400220, 0x0000401000402000, 0x0086001081220440, 0x0408800800100280,
+    0x000a001201040820, 0x8848800200840080, 0x4001000100040200, 0x0442000102105084,
+    0x9080010020804100, 0x0040404000201009, 0x0000808010002009, 0x2200090021d00100,
+    0x0008008008040080, 0x0004004002010040, 0x0011040008015042, 0x00000a0001768104,
+    0x0000800080204009, 0x2010004140002001, 0x9800200280100080, 0x1000100080080080,
+    0x0442000a00049020, 0x2100040080020080, 0x0800120400900148, 0x0010040a00128541,
+    0x2800804000800030, 0x1010002000400041, 0x4000200011004100, 0x0610008410800800,
+    0x0400802402800800, 0xc100020080800400, 0x0002000802000401, 0x0182085882000401,
+    0x0220204000808000, 0x2860100040024022, 0x0001002004110040, 0x99101042000a0020,
+    0x0004080004008080, 0x0010040002008080, 0x2012004881020004, 0x8300842444820011,
+    0x0088403882010200, 0x0820400080210100, 0x0110910040a00300, 0x0801100280080480,
+    0x0242009008200600, 0x1002000489500200, 0x0040800200010080, 0x0091800041000080,
+    0x0000209300488001, 0x04c1002414824001, 0x020020000b001041, 0x7000100004200901,
+    0x8002002004100802, 0x30010002084c0007, 0x0888221800813004, 0x4000002840840112,
+];
+
+const BISHOP_MAGICS: [u64; 64] = [
+    0xa010041108003100, 0x006082020a002900, 0x6810010619200000, 0x08281a0520000408,
+    0x0001104001000400, 0x0018901008048400, 0x00040a0210245280, 0x000200210808a402,
+    0x9140048410821200, 0x0800091010820041, 0x20504804832202c0, 0x0100091401081000,
+    0x8021011140000012, 0x0810020804450400, 0x208b0542109008a2, 0x0080084a08040204,
+    0x0040e2a80811244c, 0x2505022008008108, 0x0430220100420040, 0x010a040420220040,
+    0x1105000290400000, 0x0093001200822120, 0x4000a62048043004, 0x280120048a015004,
+    0x006090002a020814, 0x44042000240800d0, 0x01102800040a4400, 0x1004080080220040,
+    0x0001001011004024, 0x0010044000805040, 0x0914041200820100, 0x0004821012821480,
+    0x0024040500c05021, 0x0088611002080200, 0x0116080a00040020, 0x4000020080080080,
+    0x2450450140840040, 0x0000880201484100, 0x0222020404020092, 0x8081110600002e00,
+    0x2842101105000801, 0x1100809008001025, 0x00020202221c0400, 0x0422014022009020,
+    0x0210046102100c00, 0xc004008082029102, 0x00aa461801101200, 0x0404080080201108,
+    0x020542108c205002, 0x0410544804100100, 0x0040910841100000, 0x0400200042021100,
+    0x00004204850400c0, 0x0200100410a42102, 0x1040020801210102, 0x0805040410420000,
+    0x2884804130100200, 0x800c262201242000, 0x1058000194108800, 0x0014221054420204,
+    0x0104000012a02200, 0x0200881003300100, 0x0140400202840100, 0x0402020801010201,
+];
+
+#[cfg_attr(all(target_arch = "x86_64", target_feature = "bmi2"), allow(dead_code))]
+struct Magic {
+    mask: u64, // relevant blockers, the board edges never block
+    magic: u64,
+    shift: u32,
+    offset: usize,
+}
+
+impl Magic {
+    #[inline]
+    fn index(&self, occupied: u64) -> usize {
+        #[cfg(all(target_arch = "x86_64", target_feature = "bmi2"))]
+        let i = unsafe { std::arch::x86_64::_pext_u64(occupied, self.mask) };
+        #[cfg(not(all(target_arch = "x86_64", target_feature = "bmi2")))]
+        let i = (occupied & self.mask).wrapping_mul(self.magic) >> self.shift;
+        self.offset + i as usize
+    }
+}
+
+struct Sliders {
+    rook: [Magic; 64],
+    bishop: [Magic; 64],
+    attacks: Vec<u64>,
+}
+
+static SLIDERS: OnceLock<Sliders> = OnceLock::new();
+
+// slow ray casting, used to fill the tables
+fn slide(bits: u64, occupied: u64, steps: &[fn(u64) -> u64]) -> u64 {
+    let mut attacks = 0;
+    for step in steps {
+        let mut test = step(bits);
+        while test != 0 {
+            attacks |= test;
+            if test & occupied != 0 { break; }
+            test = step(test);
+        }
+    }
+    attacks
+}
+
+fn magics(magics: &[u64; 64], steps: &[fn(u64) -> u64], attacks: &mut Vec<u64>) -> [Magic; 64] {
+    std::array::from_fn(|i| {
+        let bits = 1 << i;
+        let mut mask = 0;
+        for step in steps {
+            let mut test = step(bits);
+            while step(test) != 0 {
+                mask |= test;
+                test = step(test);
+            }
+        }
+
+        let size = 1 << mask.count_ones();
+        let magic = Magic { mask, magic: magics[i], shift: 64 - mask.count_ones(), offset: attacks.len() };
+        attacks.resize(attacks.len() + size, 0);
+
+        // every subset of the mask, carry-rippler
+        let mut blockers = 0u64;
+        loop {
+            attacks[magic.index(blockers)] = slide(bits, blockers, steps);
+            blockers = blockers.wrapping_sub(mask) & mask;
+            if blockers == 0 { break; }
+        }
+        magic
+    })
+}
+
+#[inline]
+fn sliders() -> &'static Sliders {
+    SLIDERS.get_or_init(|| {
+        let mut attacks = Vec::new();
+        let rook = magics(&ROOK_MAGICS, &ROOK_STEPS, &mut attacks);
+        let bishop = magics(&BISHOP_MAGICS, &BISHOP_STEPS, &mut attacks);
+        Sliders { rook, bishop, attacks }
+    })
+}
+
+#[inline]
+pub fn knight(square: usize) -> u64 {
+    KNIGHT[square]
+}
+
+#[inline]
+pub fn king(square: usize) -> u64 {
+    KING[square]
+}
+
+// squares a pawn attacks, not where it pushes
+#[inline]
+pub fn pawn(color: Color, square: usize) -> u64 {
+    PAWN[color.index()][square]
+}
+
+#[inline]
+pub fn rook(square: usize, occupied: u64) -> u64 {
+    let s = sliders();
+    s.attacks[s.rook[square].index(occupied)]
+}
+
+#[inline]
+pub fn bishop(square: usize, occupied: u64) -> u64 {
+    let s = sliders();
+    s.attacks[s.bishop[square].index(occupied)]
+}
+
+#[inline]
+pub fn queen(square: usize, occupied: u64) -> u64 {
+    rook(square, occupied) | bishop(square, occupied)
+}
+
+// squares strictly between two squares, empty if they share no line
+#[inline]
+pub fn between(a: usize, b: usize) -> u64 {
+    let (a_bits, b_bits) = (1 << a, 1 << b);
+    if rook(a, 0) & b_bits != 0 {
+        rook(a, b_bits) & rook(b, a_bits)
+    } else if bishop(a, 0) & b_bits != 0 {
+        bishop(a, b_bits) & bishop(b, a_bits)
+    } else {
+        0
+    }
+}
+
+// the whole line through two squares from edge to edge
+#[inline]
+pub fn line(a: usize, b: usize) -> u64 {
+    let (a_bits, b_bits) = (1 << a, 1 << b);
+    if rook(a, 0) & b_bits != 0 {
+        rook(a, 0) & rook(b, 0) | a_bits | b_bits
+    } else if bishop(a, 0) & b_bits != 0 {
+        bishop(a, 0) & bishop(b, 0) | a_bits | b_bits
+    } else {
+        0
+    }
+}
+
+// the slow rays the tables are built from, for testing
+pub fn rook_rays(square: usize, occupied: u64) -> u64 {
+    slide(1 << square, occupied, &ROOK_STEPS)
+}
+
+pub fn bishop_rays(square: usize, occupied: u64) -> u64 {
+    slide(1 << square, occupied, &BISHOP_STEPS)
+}
@@ -0,0 +1,158 @@
+use super::pieces::{
+    Color,
+    Kind,
+    KINDS,
+    Pieces,
+    Piece,
+};
+
+/* Squares are numbered 0..64 from a1 to h8, square
+ * rank * 8 + file is bit 1 << square of a bit board
+ */
+pub const FILE_A: u64 = 0x0101_0101_0101_0101;
+pub const FILE_B: u64 = FILE_A << 1;
+pub const FILE_G: u64 = FILE_A << 6;
+pub const FILE_H: u64 = FILE_A << 7;
+pub const RANK_1: u64 = 0xff;
+pub const RANK_2: u64 = RANK_1 << 0x08;
+pub const RANK_7: u64 = RANK_1 << 0x30;
+pub const RANK_8: u64 = RANK_1 << 0x38;
+
+#[inline]
+pub fn square(file: usize, rank: usize) -> u64 {
+    1 << (rank * 8 + file)
+}
+
+// index of the lowest set square
+#[inline]
+pub fn index(bits: u64) -> usize {
+    bits.trailing_zeros() as usize
+}
+
+/* One square steps, pieces stepping off the
+ * board are dropped instead of wrapping a rank
+ */
+#[inline]
+pub const fn north(bits: u64) -> u64 { bits << 8 }
+#[inline]
+pub const fn south(bits: u64) -> u64 { bits >> 8 }
+#[inline]
+pub const fn east(bits: u64) -> u64 { (bits << 1) & !FILE_A }
+#[inline]
+pub const fn west(bits: u64) -> u64 { (bits >> 1) & !FILE_H }
+#[inline]
+pub const fn north_east(bits: u64) -> u64 { (bits << 9) & !FILE_A }
+#[inline]
+pub const fn north_west(bits: u64) -> u64 { (bits << 7) & !FILE_H }
+#[inline]
+pub const fn south_east(bits: u64) -> u64 { (bits >> 7) & !FILE_A }
+#[inline]
+pub const fn south_west(bits: u64) -> u64 { (bits >> 9) & !FILE_H }
+
+// iterates the set squares of a bit board, lowest first
+pub struct Bits(pub u64);
+
+impl Iterator for Bits {
+    type Item = u64;
+
+    #[inline]
+    fn next(&mut self) -> Option<u64> {
+        if self.0 == 0 { return None; }
+        let bit = self.0 & self.0.wrapping_neg();
+        self.0 ^= bit;
+        Some(bit)
+    }
+}
+
+/* One bit board per piece type and color, the Pieces
+ * of a game are built from these on demand. A mailbox
+ * of what stands on each square is kept in step with them
+ */
+#[derive(Clone, PartialEq, Debug)]
+pub struct Board {
+    bit_boards: [[u64; 6]; 2],
+    mailbox: [Option<(Kind, Color)>; 64],
+}
+
+impl Default for Board {
+    fn default() -> Self {
+        Board { bit_boards: [[0; 6]; 2], mailbox: [None; 64] }
+    }
+}
+
+impl Board {
+    pub fn from_pieces(pieces: &[Pieces]) -> Self {
+        let mut board = Board::default();
+        for p in pieces {
+            board.toggle(p.kind(), *p.color(), *p.bits());
+        }
+        board
+    }
+
+    #[inline]
+    pub fn get(&self, kind: Kind, color: Color) -> u64 {
+        self.bit_boards[color.index()][kind as usize]
+    }
+
+    #[inline]
+    pub fn color(&self, color: Color) -> u64 {
+        self.bit_boards[color.index()].iter().fold(0, |acc, b| acc | b)
+    }
+
+    #[inline]
+    pub fn occupied(&self) -> u64 {
+        self.color(Color::White) | self.color(Color::Black)
+    }
+
+    #[inline]
+    pub fn piece_at(&self, square: usize) -> Option<(Kind, Color)> {
+        self.mailbox[square]
+    }
+
+    // the piece standing on the lowest square of bits
+    #[inline]
+    pub fn kind_at(&self, bits: u64) -> Option<(Kind, Color)> {
+        if bits == 0 { return None; }
+        self.mailbox[index(bits)]
+    }
+
+    /* Adds pieces on empty squares, removes them on occupied ones,
+     * a square is only emptied in the mailbox if this piece held it
+     * so a castling king and rook can swap squares
+     */
+    #[inline]
+    pub fn toggle(&mut self, kind: Kind, color: Color, bits: u64) {
+        let board = &mut self.bit_boards[color.index()][kind as usize];
+        *board ^= bits;
+        for bit in Bits(bits) {
+            let square = &mut self.mailbox[index(bit)];
+            if *board & bit != 0 {
+                *square = Some((kind, color));
+            } else if *square == Some((kind, color)) {
+                *square = None;
+            }
+        }
+    }
+
+    // clears the squares of every bit board
+    #[inline]
+    pub fn clear(&mut self, bits: u64) {
+        for b in self.bit_boards.iter_mut().flatten() {
+            *b &= !bits;
+        }
+        for bit in Bits(bits) {
+            self.mailbox[index(bit)] = None;
+        }
+    }
+
+    /* Compatibility with the Piece trait, one Pieces per piece
+     * ordered by type from pawns to kings
+     */
+    pub fn pieces(&self) -> impl Iterator<Item = Pieces> + '_ {
+        KINDS.into_iter().flat_map(move |kind| {
+            [Color::White, Color::Black].into_iter().flat_map(move |color| {
+                Bits(self.get(kind, color)).map(move |bits| Pieces::from_kind(kind, bits, color))
+            })
+        })
+    }
+}
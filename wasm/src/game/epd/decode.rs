@@ -0,0 +1,97 @@
+use std::str::FromStr;
+
+use super::{Epd, EpdError};
+use super::super::fen;
+
+// splits the operations section into opcodes and operands,
+// operands may be quoted strings containing spaces or ';'
+fn operations(s: &str) -> Result<Vec<(String, Vec<String>)>, EpdError> {
+    let mut operations = Vec::new();
+    let mut tokens: Vec<String> = Vec::new();
+    let mut token = String::new();
+    let mut quoted = false;
+
+    for c in s.chars() {
+        match c {
+            '"' => {
+                if quoted { tokens.push(std::mem::take(&mut token)); }
+                quoted = !quoted;
+            },
+            _ if quoted => token.push(c),
+            ';' => {
+                if !token.is_empty() { tokens.push(std::mem::take(&mut token)); }
+                if tokens.is_empty() { continue; }
+                let opcode = tokens.remove(0);
+                operations.push((opcode, std::mem::take(&mut tokens)));
+            },
+            _ if c.is_whitespace() => {
+                if !token.is_empty() { tokens.push(std::mem::take(&mut token)); }
+            },
+            _ => token.push(c),
+        }
+    }
+    if quoted {
+        return Err(EpdError::UnterminatedString);
+    }
+    if !token.is_empty() { tokens.push(token); }
+    if !tokens.is_empty() {
+        // the last operation may omit its ';'
+        let opcode = tokens.remove(0);
+        operations.push((opcode, tokens));
+    }
+
+    Ok(operations)
+}
+
+// the first operand of an opcode read as the type it is kept in
+fn number<T: FromStr>(operations: &[(String, Vec<String>)], opcode: &str) -> Result<Option<T>, EpdError> {
+    operations.iter()
+        .find(|(op, _)| op == opcode)
+        .and_then(|(_, operands)| operands.first())
+        .map(|n| n.parse::<T>().map_err(|_| EpdError::BadOperand { opcode: opcode.into() }))
+        .transpose()
+}
+
+pub fn decode(epd: &str) -> Result<Epd, EpdError> {
+    // the position fields may be spaced by any run of whitespace
+    let mut rest = epd.trim();
+    let mut position = Vec::with_capacity(4);
+    for _ in 0..4 {
+        let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        if end == 0 { return Err(EpdError::FieldCount); }
+        position.push(&rest[..end]);
+        rest = rest[end..].trim_start();
+    }
+    let operations = operations(rest)?;
+
+    let single = |opcode: &str| {
+        operations.iter()
+            .find(|(op, _)| op == opcode)
+            .and_then(|(_, operands)| operands.first().cloned())
+    };
+    let list = |opcode: &str| {
+        operations.iter()
+            .find(|(op, _)| op == opcode)
+            .map(|(_, operands)| operands.clone())
+            .unwrap_or_default()
+    };
+
+    // half move clock and full move number default to "0 1"
+    let half_moves = number::<u32>(&operations, "hmvc")?.unwrap_or(0);
+    let move_count = number::<u32>(&operations, "fmvn")?.unwrap_or(1);
+    let fen = format!("{} {} {}", position.join(" "), half_moves, move_count);
+    let game = fen::decode(&fen)?;
+
+    Ok(Epd {
+        game,
+        fen,
+        best_moves: list("bm"),
+        avoid_moves: list("am"),
+        id: single("id"),
+        comment: single("c0"),
+        depth: number(&operations, "acd")?,
+        eval: number(&operations, "ce")?,
+        pv: list("pv"),
+        operations,
+    })
+}
@@ -0,0 +1,70 @@
+use std::fmt;
+use std::error::Error;
+
+use crate::error::ErrorCode;
+use super::{Game, fen::FenError};
+
+mod decode;
+pub use decode::decode;
+
+/* Extended Position Description, a FEN without the move counters
+ * followed by ';'-terminated operations. Used by test suites
+ * such as WAC, ECM, STS and Bratko-Kopec
+ * https://www.chessprogramming.org/Extended_Position_Description
+ */
+#[derive(Clone)]
+pub struct Epd {
+    pub game: Game,
+    pub fen: String,
+    pub best_moves: Vec<String>,  // bm
+    pub avoid_moves: Vec<String>, // am
+    pub id: Option<String>,       // id
+    pub comment: Option<String>,  // c0
+    pub depth: Option<u32>,       // acd
+    pub eval: Option<i32>,        // ce
+    pub pv: Vec<String>,          // pv
+
+    /* every operation in order of appearance,
+     * including opcodes not listed above
+     */
+    pub operations: Vec<(String, Vec<String>)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EpdError {
+    FieldCount,
+    UnterminatedString,
+    BadOperand { opcode: String },
+    Fen(FenError),
+}
+
+impl fmt::Display for EpdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Epd Error: ")?;
+        match self {
+            EpdError::FieldCount => write!(f, "must contain 4 space-seperated position fields"),
+            EpdError::UnterminatedString => write!(f, "unterminated string operand"),
+            EpdError::BadOperand { opcode } => write!(f, "operand of '{}' must be an integer in range", opcode),
+            EpdError::Fen(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for EpdError {}
+
+impl ErrorCode for EpdError {
+    fn code(&self) -> &'static str {
+        match self {
+            EpdError::FieldCount => "EPD_FIELD_COUNT",
+            EpdError::UnterminatedString => "EPD_UNTERMINATED_STRING",
+            EpdError::BadOperand { .. } => "EPD_BAD_OPERAND",
+            EpdError::Fen(e) => e.code(),
+        }
+    }
+}
+
+impl From<FenError> for EpdError {
+    fn from(e: FenError) -> Self {
+        EpdError::Fen(e)
+    }
+}
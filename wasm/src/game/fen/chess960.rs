@@ -0,0 +1,47 @@
+use super::FenError;
+
+// knight placements among the five squares left
+// after the bishops and queen are placed
+const KNIGHTS: [(usize, usize); 10] = [
+    (0, 1), (0, 2), (0, 3), (0, 4), (1, 2),
+    (1, 3), (1, 4), (2, 3), (2, 4), (3, 4),
+];
+
+/* Fen of a Chess960 starting position by its index 0..960,
+ * numbered as in https://www.chessprogramming.org/Reinhard_Scharnagl
+ * 518 is the standard starting position
+ */
+pub fn chess960(index: u16) -> Result<String, FenError> {
+    if index >= 960 {
+        return Err(FenError::BadChess960Index { index });
+    }
+
+    let mut n = index as usize;
+    let mut rank = [' '; 8];
+
+    rank[n % 4 * 2 + 1] = 'B'; // light square bishop
+    n /= 4;
+    rank[n % 4 * 2] = 'B'; // dark square bishop
+    n /= 4;
+
+    let mut place = |piece: char, nth: usize| {
+        let i = (0..8).filter(|&i| rank[i] == ' ').nth(nth).unwrap_or_default();
+        rank[i] = piece;
+    };
+    place('Q', n % 6);
+    n /= 6;
+
+    // the second knight's index shifts once the first is placed
+    let (a, b) = KNIGHTS[n];
+    place('N', a);
+    place('N', b - 1);
+
+    // rook, king, rook on the remaining squares
+    place('R', 0);
+    place('K', 0);
+    place('R', 0);
+
+    let white: String = rank.iter().collect();
+    let black = white.to_ascii_lowercase();
+    Ok(format!("{}/pppppppp/8/8/8/8/PPPPPPPP/{} w KQkq - 0 1", black, white))
+}
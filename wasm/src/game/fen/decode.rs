@@ -1,19 +1,17 @@
 use crate::game::util::castle::*;
-use std::error::Error;
 
 use super::{
     validate,
+    FenError,
     super::{
+        Board,
         Game,
         pieces::*,
     }
 };
 
-pub fn decode<'a>(fen: &str) -> Result<Game, Box<dyn Error>> {
+pub fn decode(fen: &str) -> Result<Game, FenError> {
     validate(fen)?;
-    let throw = || {
-         panic!("Fen Error not caught by fen validation")
-    };
 
     let fen: Vec<&str> = fen.split_whitespace().collect();
     
@@ -53,7 +51,7 @@ pub fn decode<'a>(fen: &str) -> Result<Game, Box<dyn Error>> {
                     'R' => { rooks.push(Pieces::Rook(Rook::new(x, y, Color::White))) },
                     'Q' => { queens.push(Pieces::Queen(Queen::new(x, y, Color::White))) },
                     'K' => { kings.push(Pieces::King(King::new(x, y, Color::White))) },
-                    _ => { throw(); }
+                    _ => { return Err(FenError::BadPiece { piece: c }); }
                 }
                 x += 1;
             }
@@ -70,44 +68,79 @@ pub fn decode<'a>(fen: &str) -> Result<Game, Box<dyn Error>> {
     let turn = match fen[1] {
         "w" => Color::White,
         "b" => Color::Black,
-        _ => throw()
+        _ => return Err(FenError::BadColor { color: fen[1].into() }),
     };
 
+    /* Castling rights refer to a rook by its file,
+     * KQkq name the outermost rook on that side of the king (X-FEN)
+     * and A-H, a-h name the rook's file directly (Shredder-FEN)
+     */
+    let board = Board::from_pieces(&pieces);
     let mut castling = 0u16;
-    if fen[2].contains('K') { castling += K_ID; }
-    if fen[2].contains('Q') { castling += Q_ID; }
-    if fen[2].contains('k') { castling += k_ID; }
-    if fen[2].contains('q') { castling += q_ID; }
+    let mut castling_rooks = DEFAULT_ROOKS;
+    let mut chess960 = false;
+    // by color, every KQkq right has its king and rook, and they are off e or the corners
+    let (mut backed, mut shuffled) = ([true; 2], [false; 2]);
+    for c in fen[2].chars() {
+        if c == '-' { continue; }
+        let (color, offset) = if c.is_ascii_uppercase() { (Color::White, 0) } else { (Color::Black, 2) };
+        let (home, rooks) = back_rank(&board, color);
+        let king = home.unwrap_or(4);
+        let (side, rook) = match c.to_ascii_lowercase() {
+            'k' => (0, rooks.iter().copied().filter(|&f| f > king).max()),
+            'q' => (1, rooks.iter().copied().filter(|&f| f < king).min()),
+            f @ 'a'..='h' => {
+                let file = f as u8 - b'a';
+                chess960 = true;
+                (if file > king { 0 } else { 1 }, Some(file))
+            },
+            _ => return Err(FenError::BadCastling { castling: fen[2].into() }),
+        };
+        let file = rook.unwrap_or(DEFAULT_ROOKS[side]);
+        castling |= IDS[offset + side];
+        castling_rooks[offset + side] = file;
+        if "KQkq".contains(c) {
+            backed[offset / 2] &= home.is_some() && rook.is_some();
+            shuffled[offset / 2] |= king != 4 || file != DEFAULT_ROOKS[side];
+        }
+    }
+    /* KQkq only mean Chess960 for kings and rooks that could still
+     * castle set up other than on e and in the corners, a right left
+     * over from a king or rook that moved is no sign of it
+     */
+    chess960 |= (0..2).any(|c| backed[c] && shuffled[c]);
 
-    let mut en_passant_square: u128 = 0;
+    let mut en_passant_square: u64 = 0;
     let en_passant = fen[3];
     if en_passant != "-" {
-        // liberal use of unwrap due to fen validation
-        match en_passant.chars().nth(1).unwrap() {
-            '3' => {
-                const ABC: &str = "abcdefgh";
-                let x = ABC.find(en_passant.chars().nth(0).unwrap()).unwrap();
-                en_passant_square = 1 << 0x20 + 8 + x;
-            },
-            '6' => {
-                const ABC: &str = "abcdefgh";
-                let x = ABC.find(en_passant.chars().nth(0).unwrap()).unwrap();
-                en_passant_square = 1 << 0x50 + 8 + x;
-            },
-            _ => { throw(); }
+        let bad_square = || FenError::BadEnPassant { square: en_passant.into() };
+        let mut chars = en_passant.chars();
+        let x = chars.next()
+            .and_then(|c| "abcdefgh".find(c))
+            .ok_or_else(bad_square)?;
+        match chars.next() {
+            Some('3') => { en_passant_square = 1 << (0x10 + x); },
+            Some('6') => { en_passant_square = 1 << (0x28 + x); },
+            _ => { return Err(bad_square()); }
         }
     }
 
-    let half_moves = fen[4].parse::<u16>().unwrap();
-    let move_count = fen[5].parse::<u16>().unwrap();
+    let half_moves = fen[4].parse::<u16>()
+        .map_err(|_| FenError::BadHalfMoves { number: fen[4].into() })?;
+    let move_count = fen[5].parse::<u16>()
+        .map_err(|_| FenError::BadMoveNumber { number: fen[5].into() })?;
 
-    Ok(Game::new(
+    let mut game = Game::new(
             pieces,
             turn,
             castling,
             en_passant_square,
             half_moves,
             move_count
-            ))
+            );
+    game.castling_rooks = castling_rooks;
+    game.chess960 = chess960;
+
+    Ok(game)
 }
 
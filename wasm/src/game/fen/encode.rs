@@ -1,5 +1,4 @@
 use crate::game::util::castle;
-use std::error::Error;
 
 use super::{validate, FenError};
 
@@ -9,66 +8,43 @@ use super::super::{
     notation::bits_to_algebraic
 };
 
-pub fn encode<'a>(game: &Game) -> Result<String, Box<dyn Error>> {
-    let pos = |bits: u128| {
-        for i in (0..128).step_by(16) {
-            if bits >> i & 0xff00 != 0 {
-                for j in 0..8 {
-                    if bits >> (i + j + 8) & 1 != 0 {
-                        return Some((j, i/16));
-                    }
-                }
-            }
-        }
-        None
-    };
-
-    let mut board: Vec<Vec<char>> = vec![vec!['.'; 8]; 8];
+/* How castling rights are written, X-FEN uses KQkq
+ * unless another rook stands further out on the same side,
+ * Shredder-FEN always names the rook's file
+ */
+#[derive(Clone, Copy, PartialEq)]
+pub enum CastlingNotation {
+    XFen,
+    Shredder,
+}
 
-    for p in game.pieces.iter() {
-        let xy = pos(*p.bits()).ok_or_else(|| FenError("Invalid bit boards".into()))?;
-        match p {
-            Pieces::Pawn(p) => {
-                if *p.color() == Color::White { board[xy.1][xy.0] = 'P'; }
-                else { board[xy.1][xy.0] = 'p'; }
-            },
-            Pieces::Knight(n) => {
-                if *n.color() == Color::White { board[xy.1][xy.0] = 'N'; }
-                else { board[xy.1][xy.0] = 'n'; }
-            },
-            Pieces::Bishop(b) => {
-                if *b.color() == Color::White { board[xy.1][xy.0] = 'B'; }
-                else { board[xy.1][xy.0] = 'b'; }
-            },
-            Pieces::Rook(r) => {
-                if *r.color() == Color::White { board[xy.1][xy.0] = 'R'; }
-                else { board[xy.1][xy.0] = 'r'; }
-            },
-            Pieces::Queen(q) => {
-                if *q.color() == Color::White { board[xy.1][xy.0] = 'Q'; }
-                else { board[xy.1][xy.0] = 'q'; }
-            },
-            Pieces::King(k) => {
-                if *k.color() == Color::White { board[xy.1][xy.0] = 'K'; }
-                else { board[xy.1][xy.0] = 'k'; }
-            },
-        }
-    }
+pub fn encode(game: &Game) -> Result<String, FenError> {
+    encode_with(game, CastlingNotation::XFen)
+}
 
+pub fn encode_with(game: &Game, notation: CastlingNotation) -> Result<String, FenError> {
     let mut fen = String::new();
 
-    for v in board.iter().rev() {
+    for rank in (0..8).rev() {
         let mut empty_count = 0;
-        for c in v {
-            if *c == '.' {
+        for file in 0..8 {
+            let Some((kind, color)) = game.board.piece_at(rank * 8 + file) else {
                 empty_count += 1;
                 continue;
-            }
+            };
             if empty_count != 0 {
                 fen.push_str(&empty_count.to_string());
                 empty_count = 0;
             }
-            fen.push(*c);
+            let c = match kind {
+                Kind::Pawn => 'p',
+                Kind::Knight => 'n',
+                Kind::Bishop => 'b',
+                Kind::Rook => 'r',
+                Kind::Queen => 'q',
+                Kind::King => 'k',
+            };
+            fen.push(if color == Color::White { c.to_ascii_uppercase() } else { c });
         }
         if empty_count != 0 {
             fen.push_str(&empty_count.to_string());
@@ -85,10 +61,24 @@ pub fn encode<'a>(game: &Game) -> Result<String, Box<dyn Error>> {
     fen.push(' ');
 
     let mut castling = String::new();
-    if game.castling & castle::K_ID != 0 { castling.push('K'); }
-    if game.castling & castle::Q_ID != 0 { castling.push('Q'); }
-    if game.castling & castle::k_ID != 0 { castling.push('k'); }
-    if game.castling & castle::q_ID != 0 { castling.push('q'); }
+    for (i, id) in castle::IDS.iter().enumerate() {
+        if game.castling & id == 0 { continue; }
+        let color = if i < 2 { Color::White } else { Color::Black };
+        let (king, rooks) = castle::back_rank(&game.board, color);
+        let king = king.unwrap_or(4);
+        let file = game.castling_rooks[i];
+
+        let outermost = match i % 2 {
+            0 => rooks.iter().all(|&f| f <= king || f <= file),
+            _ => rooks.iter().all(|&f| f >= king || f >= file),
+        };
+        let c = match (notation, outermost, i % 2) {
+            (CastlingNotation::XFen, true, 0) => 'k',
+            (CastlingNotation::XFen, true, _) => 'q',
+            _ => (b'a' + file) as char,
+        };
+        castling.push(if color == Color::White { c.to_ascii_uppercase() } else { c });
+    }
     if game.castling == 0 { castling.push('-'); }
     fen.push_str(castling.as_str());
 
@@ -102,7 +92,7 @@ pub fn encode<'a>(game: &Game) -> Result<String, Box<dyn Error>> {
     fen.push_str(&game.half_moves.to_string());
     fen.push(' ');
     fen.push_str(&game.move_count.to_string());
-    validate(&fen).ok();
+    validate(&fen)?;
 
     Ok(fen)
 }
@@ -1,20 +1,115 @@
 use std::fmt;
 use std::error::Error;
 
+use crate::error::ErrorCode;
+use super::pieces::Color;
+
 mod validate;
 pub use validate::validate;
 mod encode;
-pub use encode::encode;
+pub use encode::{encode, encode_with, CastlingNotation};
 mod decode;
 pub use decode::decode;
+mod strict;
+pub use strict::validate_strict;
+mod chess960;
+pub use chess960::chess960;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FenError {
+    FieldCount { found: usize },
+    BadMoveNumber { number: String },
+    BadHalfMoves { number: String },
+    BadEnPassant { square: String },
+    BadCastling { castling: String },
+    BadColor { color: String },
+    RowCount { found: usize },
+    ConsecutiveNumbers { row: usize },
+    BadPiece { piece: char },
+    RowLength { row: usize },
+    TooManyKings,
+    TooFewKings,
+    BadBitBoard,
+    BadChess960Index { index: u16 },
 
-#[derive(Debug)]
-pub struct FenError(String);
+    /* strict validation */
+    TooManyPawns { color: Color },
+    TooManyPieces { color: Color },
+    TooManyPromotions { color: Color },
+    PawnOnBackRank { square: String },
+    BadCastlingRights { right: char },
+    EnPassantNoPawn { square: String },
+    OpponentInCheck,
+}
 
 impl fmt::Display for FenError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Fen Error: {}", self.0)
+        write!(f, "Fen Error: ")?;
+        match self {
+            FenError::FieldCount { found } =>
+                write!(f, "must contain 6 space-seperated fields, found {}", found),
+            FenError::BadMoveNumber { number } =>
+                write!(f, "move number must be positive integer, found '{}'", number),
+            FenError::BadHalfMoves { number } =>
+                write!(f, "half move counter must be positive integer, found '{}'", number),
+            FenError::BadEnPassant { square } =>
+                write!(f, "invalid en-passant square '{}'", square),
+            FenError::BadCastling { castling } =>
+                write!(f, "invalid castling availability '{}'", castling),
+            FenError::BadColor { color } =>
+                write!(f, "side-to-move '{}' is invalid", color),
+            FenError::RowCount { found } =>
+                write!(f, "piece data must contain 8 '/'-seperated rows, found {}", found),
+            FenError::ConsecutiveNumbers { row } =>
+                write!(f, "piece data is invalid, consecutive number in row {}", row),
+            FenError::BadPiece { piece } =>
+                write!(f, "piece data is invalid, invalid piece '{}'", piece),
+            FenError::RowLength { row } =>
+                write!(f, "piece data is invalid, wrong number of squares in row {}", row),
+            FenError::TooManyKings => write!(f, "too many kings"),
+            FenError::TooFewKings => write!(f, "too few kings"),
+            FenError::BadBitBoard => write!(f, "invalid bit boards"),
+            FenError::BadChess960Index { index } => write!(f, "Chess960 index {} is not in 0..960", index),
+            FenError::TooManyPawns { color } => write!(f, "{:?} has more than 8 pawns", color),
+            FenError::TooManyPieces { color } => write!(f, "{:?} has more than 16 pieces", color),
+            FenError::TooManyPromotions { color } =>
+                write!(f, "{:?} has more promoted pieces than missing pawns", color),
+            FenError::PawnOnBackRank { square } => write!(f, "pawn on back rank at {}", square),
+            FenError::BadCastlingRights { right } =>
+                write!(f, "castling right '{}' without king and rook on their home squares", right),
+            FenError::EnPassantNoPawn { square } =>
+                write!(f, "no pawn could have double-pushed past en-passant square {}", square),
+            FenError::OpponentInCheck => write!(f, "side not to move is in check"),
+        }
     }
 }
 
 impl Error for FenError {}
+
+impl ErrorCode for FenError {
+    fn code(&self) -> &'static str {
+        match self {
+            FenError::FieldCount { .. } => "FEN_FIELD_COUNT",
+            FenError::BadMoveNumber { .. } => "FEN_BAD_MOVE_NUMBER",
+            FenError::BadHalfMoves { .. } => "FEN_BAD_HALF_MOVES",
+            FenError::BadEnPassant { .. } => "FEN_BAD_EN_PASSANT",
+            FenError::BadCastling { .. } => "FEN_BAD_CASTLING",
+            FenError::BadColor { .. } => "FEN_BAD_COLOR",
+            FenError::RowCount { .. } => "FEN_ROW_COUNT",
+            FenError::ConsecutiveNumbers { .. } => "FEN_CONSECUTIVE_NUMBERS",
+            FenError::BadPiece { .. } => "FEN_BAD_PIECE",
+            FenError::RowLength { .. } => "FEN_ROW_LENGTH",
+            FenError::TooManyKings => "FEN_TOO_MANY_KINGS",
+            FenError::TooFewKings => "FEN_TOO_FEW_KINGS",
+            FenError::BadBitBoard => "FEN_BAD_BIT_BOARD",
+            FenError::BadChess960Index { .. } => "FEN_BAD_CHESS960_INDEX",
+            FenError::TooManyPawns { .. } => "FEN_TOO_MANY_PAWNS",
+            FenError::TooManyPieces { .. } => "FEN_TOO_MANY_PIECES",
+            FenError::TooManyPromotions { .. } => "FEN_TOO_MANY_PROMOTIONS",
+            FenError::PawnOnBackRank { .. } => "FEN_PAWN_ON_BACK_RANK",
+            FenError::BadCastlingRights { .. } => "FEN_BAD_CASTLING_RIGHTS",
+            FenError::EnPassantNoPawn { .. } => "FEN_EN_PASSANT_NO_PAWN",
+            FenError::OpponentInCheck => "FEN_OPPONENT_IN_CHECK",
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use crate::game::{
+    pieces::{Color, Piece, Pieces},
+    notation::bits_to_algebraic,
+    util::{castle, promote},
+};
+use super::{decode, FenError};
+
+/* Positions that are well formed but could never arise
+ * in a game, validate() stays lenient so puzzles and
+ * studies can still set up such positions
+ */
+pub fn validate_strict(fen: &str) -> Result<(), Vec<FenError>> {
+    let game = decode(fen).map_err(|e| vec![e])?;
+    let mut errors = Vec::new();
+
+    let occupied = game.board.occupied();
+
+    // material must be reachable from the starting position
+    for color in [Color::White, Color::Black] {
+        let (mut pawns, mut knights, mut bishops, mut rooks, mut queens, mut total) = (0, 0, 0, 0, 0, 0);
+        for p in game.pieces().filter(|p| *p.color() == color) {
+            total += 1;
+            match p {
+                Pieces::Pawn(_) => pawns += 1,
+                Pieces::Knight(_) => knights += 1,
+                Pieces::Bishop(_) => bishops += 1,
+                Pieces::Rook(_) => rooks += 1,
+                Pieces::Queen(_) => queens += 1,
+                Pieces::King(_) => (),
+            }
+        }
+        if pawns > 8 {
+            errors.push(FenError::TooManyPawns { color });
+        }
+        if total > 16 {
+            errors.push(FenError::TooManyPieces { color });
+        }
+        let promoted = (queens - 1).max(0) + (rooks - 2).max(0) + (bishops - 2).max(0) + (knights - 2).max(0);
+        if pawns <= 8 && pawns + promoted > 8 {
+            errors.push(FenError::TooManyPromotions { color });
+        }
+    }
+
+    for p in game.pieces() {
+        if let Pieces::Pawn(_) = p {
+            if p.bits() & (promote::WHITE_BACK_RANK | promote::BLACK_BACK_RANK) != 0 {
+                let square = bits_to_algebraic(p.bits()).unwrap_or_default();
+                errors.push(FenError::PawnOnBackRank { square });
+            }
+        }
+    }
+
+    // castling rights need the king on its back rank and the
+    // rook they refer to on the matching side of it
+    for (i, id) in castle::IDS.iter().enumerate() {
+        if game.castling & id == 0 { continue; }
+        let color = if i < 2 { Color::White } else { Color::Black };
+        let (king, rooks) = castle::back_rank(&game.board, color);
+        let rook = game.castling_rooks[i];
+        let side = match king {
+            Some(king) if i % 2 == 0 => rook > king,
+            Some(king) => rook < king,
+            None => false,
+        };
+        if !side || !rooks.contains(&rook) {
+            let right = ['K', 'Q', 'k', 'q'][i];
+            errors.push(FenError::BadCastlingRights { right });
+        }
+    }
+
+    // en passant needs a pawn that just double-pushed past the square
+    if game.en_passant_square != 0 {
+        let ep = game.en_passant_square;
+        let (pawn, origin, color) = match game.turn {
+            Color::White => (ep >> 0x08, ep << 0x08, Color::Black),
+            Color::Black => (ep << 0x08, ep >> 0x08, Color::White),
+        };
+        let pushed = game.pieces()
+            .any(|p| matches!(p, Pieces::Pawn(_)) && *p.color() == color && p.bits() & pawn != 0);
+        if !pushed || occupied & (ep | origin) != 0 {
+            let square = bits_to_algebraic(&ep).unwrap_or_default();
+            errors.push(FenError::EnPassantNoPawn { square });
+        }
+    }
+
+    // the side that just moved cannot have left its king in check
+    let mut other = game.clone();
+    other.en_passant_square = 0;
+    other.turn = match game.turn {
+        Color::White => Color::Black,
+        Color::Black => Color::White,
+    };
+    match other.info() {
+        Ok(info) if !info.check => (),
+        _ => errors.push(FenError::OpponentInCheck),
+    }
+
+    if errors.is_empty() { Ok(()) } else { Err(errors) }
+}
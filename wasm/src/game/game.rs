@@ -1,10 +1,15 @@
-use std::error::Error;
 use super::{
+    Board,
     GameError,
+    MoveError,
+    board::Bits,
+    notation::bits_to_algebraic,
+    zobrist,
     pieces::{
         Color,
         Color::White,
         Color::Black,
+        Kind,
         Piece,
         Pieces,
     },
@@ -13,10 +18,12 @@ use super::{
 
 #[derive(Clone)]
 pub struct Game {
-    pub pieces: Vec<Pieces>,
+    pub board: Board,
     pub turn: Color,
     pub castling: u16,
-    pub en_passant_square: u128,
+    pub castling_rooks: [u8; 4], // rook file for each of K, Q, k, q
+    pub chess960: bool,
+    pub en_passant_square: u64,
     pub half_moves: u16,
     pub move_count: u16,
 }
@@ -26,18 +33,43 @@ impl Game {
         pieces: Vec<Pieces>,
         turn: Color,
         castling: u16,
-        en_passant_square: u128,
+        en_passant_square: u64,
         half_moves: u16,
         move_count: u16
     ) -> Self {
-        Game { pieces, turn, castling, en_passant_square, half_moves, move_count }
+        Game {
+            board: Board::from_pieces(&pieces),
+            turn,
+            castling,
+            castling_rooks: castle::DEFAULT_ROOKS,
+            chess960: false,
+            en_passant_square,
+            half_moves,
+            move_count,
+        }
+    }
+
+    // every piece on the board, pawns first
+    pub fn pieces(&self) -> impl Iterator<Item = Pieces> + '_ {
+        self.board.pieces()
+    }
+
+    // the piece on a square 0..64, a1 is 0 and h8 is 63
+    pub fn piece_at(&self, square: usize) -> Option<Pieces> {
+        self.board.piece_at(square)
+            .map(|(kind, color)| Pieces::from_kind(kind, 1 << square, color))
     }
 
-    pub fn moves(&self) -> Result<Vec<Pieces>, Box<dyn Error>> {
+    // Zobrist hash of the position
+    pub fn hash(&self) -> u64 {
+        zobrist::hash(self)
+    }
+
+    pub fn moves(&self) -> Result<Vec<Pieces>, GameError> {
         Ok(self.moves_verbose()?.0)
     }
 
-    pub fn info(&self) -> Result<GameInfo, Box<dyn Error>> {
+    pub fn info(&self) -> Result<GameInfo, GameError> {
         // high overhead, prefer moves_verbose
         // in almost all situations
         Ok(self.moves_verbose()?.1)
@@ -46,147 +78,130 @@ impl Game {
     /* Moves are added independent of color using team and opp prefixes
      * essentially treating the color's turn as maximizing or minimizing
      */
-    pub fn moves_verbose(&self) -> Result<(Vec<Pieces>, GameInfo), Box<dyn Error>> {
+    pub fn moves_verbose(&self) -> Result<(Vec<Pieces>, GameInfo), GameError> {
         let mut moves: Vec<Pieces> = Vec::with_capacity(64);
-        let mut info = GameInfo::init(&self);
-
-        /* Add moves, save king to be evaluated later
-         * King moves can only be determined once opposition
-         * attacks are known to prevent moving into check
-         */
-        core::gen_moves(&mut info, &self, &mut moves);
+        let mut info = GameInfo::init(self)?;
 
-        /* Determine king moves using opp_attacks
-         * to prevent walking into check
+        /* Checkers and pins are known from init so every
+         * move added here is legal, king moves wait on the
+         * opposition attacks to prevent walking into check
          */
-        if info.opp_attacks & info.king.bits() != 0 {
-            info.check = true;
-            moves.clear();
-        }
+        core::gen_moves(&info, self, &mut moves);
+        core::gen_attacks(&mut info, self);
         info.king.moves(&info.opp_attacks, &info.team_pieces, &mut moves);
 
         /* Adds the castling options if valid
-         * a bit is added indicating which option 
-         * is chosen for promotion Q, R, B, N
+         * the king and rook files come from the castling
+         * rights so any Chess960 setup is handled
          */
         if !info.check {
-            let obstacles = (info.opp_attacks & !castle::EDGE_CASE) | info.opp_pieces | info.team_pieces;
-            castle::add_castling(self.castling, &obstacles, self.turn, &mut moves);
+            castle::add_castling(self, &info, &mut moves);
         }
 
-        /* trim moves to disgard moving pinned pieces
-         * casts feelers from king and detects if peice
-         * is checking him
-         */
-        if !info.check {
-            let moves = moves.into_iter().filter(|m| {
-                if let Pieces::King(_) = *m { return true; }
-                check::filter_pins(&info, &self, &m.bits())
-            }).collect::<Vec<Pieces>>();
-            info.valid_moves = moves.len() as u16;
-            if info.valid_moves == 0 {
-                info.stalemate = true;
-            }
-            return Ok((moves, info));
+        info.valid_moves = moves.len() as u16;
+        if info.valid_moves == 0 {
+            info.checkmate = info.check;
+            info.stalemate = !info.check;
         }
+        Ok((moves, info))
+    }
 
-        /* Gen moves that are check-safe
-         * does this by casting king to another piece
-         * and gen moves. Compare the king's sight
-         * to attack piece sight and allows for blocking the check
-         */
-        check::gen_check_moves(&mut info, &self, &mut moves)?;
+    // opp pieces giving check, for highlighting
+    pub fn checkers(&self) -> Result<u64, GameError> {
+        Ok(GameInfo::init(self)?.checkers)
+    }
 
-        let moves = moves.into_iter().filter(|m| {
-            if let Pieces::King(_) = *m { return true; }
-            check::filter_pins(&info, &self, &m.bits())
-        }).collect::<Vec<Pieces>>();
-        info.valid_moves = moves.len() as u16;
-        if info.valid_moves == 0 && info.check {
-            info.checkmate = true;
-        }
-        return Ok((moves, info));
+    // team pieces pinned to their king, for highlighting
+    pub fn pinned(&self) -> Result<u64, GameError> {
+        Ok(GameInfo::init(self)?.pinned)
     }
 
-    pub fn move_piece(&mut self, mut mv: u128) {
-        let mut remove: Option<usize> = None;
-        let mut ep_remove: Option<usize> = None;
-
-        let mut ep_this_turn = false;
-        let mut two_pawn_moves = (false, false);
-        for (i, piece) in self.pieces.iter_mut().enumerate() {
-            if *piece.color() == self.turn && piece.bits() & mv != 0 {
-                // if pawn move and is en passant, add en passant,
-                if let Pieces::Pawn(_) = piece {
-                    two_pawn_moves.0 = true;
-                    if let Some(ep) = en_passant::add_en_passant(&mv) {
-                        self.en_passant_square = ep;
-                        ep_this_turn = true;
-                    }
-                    promote::try_promote(piece, &mut mv, self.turn);
-                }
+    pub fn move_piece(&mut self, mut mv: u64) {
+        if let Some(c) = castle::castle_move(self, &mv) {
+            castle::try_castle(self, &c);
+            self.half_moves = self.half_moves.saturating_add(1);
+            self.next_turn();
+            return;
+        }
 
-                match mv {
-                    castle::K_ZONE => {
-                        castle::try_castle(piece, castle::K_MOVE, castle::K_ROOK);
-                    },
-                    castle::Q_ZONE => {
-                        castle::try_castle(piece, castle::Q_MOVE, castle::Q_ROOK);
-                    },
-                    castle::k_ZONE => {
-                        castle::try_castle(piece, castle::k_MOVE, castle::k_ROOK);
-                    },
-                    castle::q_ZONE => {
-                        castle::try_castle(piece, castle::q_MOVE, castle::q_ROOK);
-                    },
-                    _ => piece.set_bits(&(piece.bits() ^ mv))
-                };
-
-                self.castling = castle::fix_castle(self.castling, &mv);
-            }
-            else if piece.bits() & !mv == 0 {
-                remove = Some(i);
-            }
-            else if let Pieces::Pawn(p) = piece {
-                if self.en_passant_square & mv == 0 { continue; }
-                let en_passant_square: u128;
-                match p.color() {
-                    White => en_passant_square = self.en_passant_square << 0x10,
-                    Black => en_passant_square = self.en_passant_square >> 0x10,
+        let (us, them) = (self.turn, self.turn.opponent());
+
+        // a promotion's indicator can share a square with one of our
+        // pieces, strip it before looking for the moving piece
+        let mut promotion = None;
+        if mv & self.board.get(Kind::Pawn, us) != 0 {
+            promotion = promote::try_promote(&mut mv, us);
+        }
+
+        let src = mv & self.board.color(us);
+        let dst = mv & !src;
+        let Some((kind, _)) = self.board.kind_at(src) else { return; };
+
+        let mut capture = self.board.kind_at(dst).is_some_and(|(_, color)| color == them);
+        self.board.clear(dst);
+        self.board.toggle(kind, us, src);
+        self.board.toggle(promotion.unwrap_or(kind), us, dst);
+
+        let mut en_passant_square = 0;
+        match kind {
+            Kind::Pawn => {
+                if dst & self.en_passant_square != 0 {
+                    let pawn = match us {
+                        White => dst >> 0x08,
+                        Black => dst << 0x08,
+                    };
+                    self.board.toggle(Kind::Pawn, them, pawn);
+                    capture = true;
                 }
-                if p.bits() & en_passant_square != 0 {
-                    two_pawn_moves.1 = true;
-                    ep_remove = Some(i);
+                if let Some(ep) = en_passant::add_en_passant(&mv) {
+                    en_passant_square = ep;
                 }
-            }
-        } // end for loop
-
-        if !ep_this_turn { self.en_passant_square = 0u128; }
-        if two_pawn_moves == (true, true) { remove = ep_remove; }
+            },
+            Kind::King => self.castling = castle::remove_rights(self.castling, us),
+            _ => (),
+        }
+        self.en_passant_square = en_passant_square;
+        self.castling = castle::fix_castle(self.castling, &self.castling_rooks, &mv);
 
-        if let Some(i) = remove {
-            self.pieces.remove(i);
+        // the fifty move rule counts from the last capture or pawn move
+        if capture || kind == Kind::Pawn {
             self.half_moves = 0;
         } else {
-            self.half_moves += 1;
+            self.half_moves = self.half_moves.saturating_add(1);
         }
 
+        self.next_turn();
+    }
+
+    // passes the turn, used by null move pruning
+    pub fn null_move(&mut self) {
+        self.en_passant_square = 0;
+        self.half_moves = self.half_moves.saturating_add(1);
+        self.next_turn();
+    }
+
+    fn next_turn(&mut self) {
         match self.turn {
             White => self.turn = Black,
             Black => {
-                self.move_count += 1;
+                self.move_count = self.move_count.saturating_add(1);
                 self.turn = White;
             }
         }
     }
 
-    pub fn valid_move(&self, mv: &u128) -> Result<(), Box<dyn Error>> {
+    pub fn valid_move(&self, mv: &u64) -> Result<(), MoveError> {
         for m in &self.moves()? {
             if m.bits() ^ mv == 0 {
                 return Ok(())
             }
         }
-        Err(Box::new(GameError("Invalid Move".into())))
+
+        // name the squares the move touches
+        let squares = Bits(*mv)
+            .filter_map(|bit| bits_to_algebraic(&bit).ok())
+            .collect::<String>();
+        Err(MoveError::Illegal { mv: squares })
     }
 }
 
@@ -3,23 +3,87 @@ use std::{
     fmt
 };
 
+use crate::error::ErrorCode;
+use pieces::Color;
+
 pub mod fen;
+pub mod epd;
 pub mod notation;
 pub mod pieces;
+pub mod board;
+pub mod attacks;
+pub use board::Board;
 pub mod game;
 pub use game::Game;
 pub mod util;
+pub mod zobrist;
+pub mod perft;
 
-// used for off-board detection
-pub const MASK: u128 = 0xff00_ff00_ff00_ff00_ff00_ff00_ff00_ff00;
-
-#[derive(Debug)]
-pub struct GameError(String);
+/* Positions move generation cannot work with,
+ * only reachable from hand-built or unvalidated games
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub enum GameError {
+    MissingKing { color: Color },
+    MissingAttacker,
+    KingGivesCheck,
+}
 
 impl fmt::Display for GameError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Game Error: {}", self.0)
+        write!(f, "Game Error: ")?;
+        match self {
+            GameError::MissingKing { color } => write!(f, "{:?} has no king", color),
+            GameError::MissingAttacker => write!(f, "king in check without an attacker"),
+            GameError::KingGivesCheck => write!(f, "king is checking another king"),
+        }
     }
 }
 
 impl Error for GameError {}
+
+impl ErrorCode for GameError {
+    fn code(&self) -> &'static str {
+        match self {
+            GameError::MissingKing { .. } => "GAME_MISSING_KING",
+            GameError::MissingAttacker => "GAME_MISSING_ATTACKER",
+            GameError::KingGivesCheck => "GAME_KING_GIVES_CHECK",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MoveError {
+    Illegal { mv: String },
+    BadPromotion { piece: String },
+    Game(GameError),
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Move Error: ")?;
+        match self {
+            MoveError::Illegal { mv } => write!(f, "illegal move {}", mv),
+            MoveError::BadPromotion { piece } => write!(f, "invalid promotion '{}'", piece),
+            MoveError::Game(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for MoveError {}
+
+impl ErrorCode for MoveError {
+    fn code(&self) -> &'static str {
+        match self {
+            MoveError::Illegal { .. } => "MOVE_ILLEGAL",
+            MoveError::BadPromotion { .. } => "MOVE_BAD_PROMOTION",
+            MoveError::Game(e) => e.code(),
+        }
+    }
+}
+
+impl From<GameError> for MoveError {
+    fn from(e: GameError) -> Self {
+        MoveError::Game(e)
+    }
+}
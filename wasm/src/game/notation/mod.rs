@@ -3,22 +3,60 @@ pub use algebraic::{
     bits_to_algebraic,
     algebraic_to_bits,
 };
+pub mod san;
+pub use san::{
+    to_san,
+    from_san,
+};
+pub mod uci;
+pub use uci::{
+    to_uci,
+    from_uci,
+};
 
 use core::fmt;
 use std::error::Error;
-#[derive(Debug)]
-pub struct NotationError<'a> {
-    pub error: &'a str,
+
+use crate::error::ErrorCode;
+use super::GameError;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum NotationError {
+    BadSquare { square: String },
+    NoSquare,
+    UnknownMove { san: String },
+    UnknownUci { uci: String },
+    Game(GameError),
 }
 
-impl<'a> fmt::Display for NotationError<'a> {
+impl fmt::Display for NotationError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Invalid Notation")
+        match self {
+            NotationError::BadSquare { square } => write!(f, "Invalid Notation: '{}' is not a square", square),
+            NotationError::NoSquare => write!(f, "Invalid Notation: bit board holds no square"),
+            NotationError::UnknownMove { san } => write!(f, "Invalid Notation: no legal move matches '{}'", san),
+            NotationError::UnknownUci { uci } => write!(f, "Invalid Notation: no legal move matches '{}'", uci),
+            NotationError::Game(e) => write!(f, "Invalid Notation: {}", e),
+        }
+    }
+}
+
+impl Error for NotationError {}
+
+impl From<GameError> for NotationError {
+    fn from(e: GameError) -> Self {
+        NotationError::Game(e)
     }
 }
 
-impl<'a> Error for NotationError<'a> {
-    fn description(&self) -> &'a str {
-        &self.error
+impl ErrorCode for NotationError {
+    fn code(&self) -> &'static str {
+        match self {
+            NotationError::BadSquare { .. } => "NOTATION_BAD_SQUARE",
+            NotationError::NoSquare => "NOTATION_NO_SQUARE",
+            NotationError::UnknownMove { .. } => "NOTATION_UNKNOWN_MOVE",
+            NotationError::UnknownUci { .. } => "NOTATION_UNKNOWN_UCI",
+            NotationError::Game(e) => e.code(),
+        }
     }
 }
@@ -0,0 +1,121 @@
+use crate::game::{
+    Game,
+    pieces::{
+        Color,
+        Piece,
+        Pieces,
+    },
+    util::{castle, promote},
+};
+use super::{NotationError, bits_to_algebraic};
+
+// splits a move into its source and destination squares
+// and the promotion piece, if any
+pub(super) fn squares(game: &Game, mv: &Pieces) -> (u64, u64, Option<char>) {
+    let current = game.board.color(game.turn);
+
+    let mut src = current & mv.bits();
+    let mut dst = mv.bits() & !src;
+    let mut promotion = None;
+    if let Pieces::Pawn(p) = mv {
+        let back_rank = match p.color() {
+            Color::White => promote::BLACK_BACK_RANK,
+            Color::Black => promote::WHITE_BACK_RANK,
+        };
+        src &= !back_rank;
+        dst &= !back_rank;
+        if dst & (promote::WHITE_BACK_RANK | promote::BLACK_BACK_RANK) != 0 {
+            promotion = match p.bits() ^ (src | dst) {
+                promote::WHITE_ROOK | promote::BLACK_ROOK => Some('R'),
+                promote::WHITE_KNIGHT | promote::BLACK_KNIGHT => Some('N'),
+                promote::WHITE_BISHOP | promote::BLACK_BISHOP => Some('B'),
+                _ => Some('Q'),
+            };
+        }
+    }
+
+    (src, dst, promotion)
+}
+
+fn letter(piece: &Pieces) -> &'static str {
+    match piece {
+        Pieces::Pawn(_) => "",
+        Pieces::Bishop(_) => "B",
+        Pieces::Knight(_) => "N",
+        Pieces::Rook(_) => "R",
+        Pieces::Queen(_) => "Q",
+        Pieces::King(_) => "K",
+    }
+}
+
+/* Standard Algebraic Notation for a move in the game's move list,
+ * with disambiguation and check/checkmate suffixes
+ */
+pub fn to_san(game: &Game, mv: &Pieces) -> Result<String, NotationError> {
+    let moves = game.moves()?;
+
+    let mut san = match castle::castle_move(game, mv.bits()) {
+        Some(c) if c.right % 2 == 0 => String::from("O-O"),
+        Some(_) => String::from("O-O-O"),
+        None => {
+            let (src, dst, promotion) = squares(game, mv);
+            let from = bits_to_algebraic(&src)?;
+            let to = bits_to_algebraic(&dst)?;
+
+            let opp = game.board.color(game.turn.opponent());
+            let pawn = matches!(mv, Pieces::Pawn(_));
+            let capture = dst & opp != 0 || (pawn && dst & game.en_passant_square != 0);
+
+            let mut san = String::from(letter(mv));
+            if pawn {
+                if capture { san.push_str(&from[..1]); }
+            } else {
+                // other pieces of the same kind able to reach dst
+                let (mut file, mut rank, mut ambiguous) = (false, false, false);
+                for m in &moves {
+                    if std::mem::discriminant(m) != std::mem::discriminant(mv) { continue; }
+                    let (s, d, _) = squares(game, m);
+                    if d != dst || s == src { continue; }
+                    let other = bits_to_algebraic(&s)?;
+                    ambiguous = true;
+                    if other[..1] == from[..1] { file = true; }
+                    if other[1..] == from[1..] { rank = true; }
+                }
+                if ambiguous && !file { san.push_str(&from[..1]); }
+                else if ambiguous && !rank { san.push_str(&from[1..]); }
+                else if ambiguous { san.push_str(&from); }
+            }
+            if capture { san.push('x'); }
+            san.push_str(&to);
+            if let Some(p) = promotion {
+                san.push('=');
+                san.push(p);
+            }
+            san
+        }
+    };
+
+    let mut game_copy = game.clone();
+    game_copy.move_piece(*mv.bits());
+    if let Ok(info) = game_copy.info() {
+        if info.checkmate { san.push('#'); }
+        else if info.check { san.push('+'); }
+    }
+
+    Ok(san)
+}
+
+/* Finds the move in the game's move list matching a SAN string,
+ * check, checkmate and annotation suffixes are ignored
+ */
+pub fn from_san(game: &Game, san: &str) -> Result<Pieces, NotationError> {
+    let strip = |s: &str| s.trim_end_matches(['+', '#', '!', '?']).replace('0', "O");
+    let target = strip(san);
+
+    for mv in game.moves()? {
+        if strip(&to_san(game, &mv)?) == target {
+            return Ok(mv);
+        }
+    }
+    Err(NotationError::UnknownMove { san: san.into() })
+}
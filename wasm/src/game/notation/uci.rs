@@ -0,0 +1,34 @@
+use crate::game::{
+    Game,
+    pieces::{Piece, Pieces},
+    util::castle,
+};
+use super::{NotationError, bits_to_algebraic, san::squares};
+
+/* Long algebraic notation as used by UCI, e.g. e2e4 or e7e8q.
+ * Castles are the king's two square move in standard chess
+ * and the king taking its own rook in Chess960
+ */
+pub fn to_uci(game: &Game, mv: &Pieces) -> Result<String, NotationError> {
+    if let Some(c) = castle::castle_move(game, mv.bits()) {
+        let to = if game.chess960 { c.rook_from } else { c.king_to };
+        return Ok(bits_to_algebraic(&c.king_from)? + &bits_to_algebraic(&to)?);
+    }
+
+    let (src, dst, promotion) = squares(game, mv);
+    let mut uci = bits_to_algebraic(&src)? + &bits_to_algebraic(&dst)?;
+    if let Some(p) = promotion {
+        uci.push(p.to_ascii_lowercase());
+    }
+    Ok(uci)
+}
+
+// finds the move in the game's move list matching a UCI string
+pub fn from_uci(game: &Game, uci: &str) -> Result<Pieces, NotationError> {
+    for mv in game.moves()? {
+        if to_uci(game, &mv)? == uci {
+            return Ok(mv);
+        }
+    }
+    Err(NotationError::UnknownUci { uci: uci.into() })
+}
@@ -0,0 +1,155 @@
+use std::{
+    ops::Add,
+    sync::atomic::{AtomicU64, Ordering::Relaxed},
+};
+use rayon::prelude::*;
+
+use super::{
+    Game,
+    notation::{to_uci, NotationError},
+    pieces::{Kind, Piece},
+    util::{GameInfo, castle},
+};
+
+/* Perft counts the leaf nodes of the move tree to a depth,
+ * the last ply is bulk counted from the length of the move
+ * list instead of making each move. More info at:
+ * https://www.chessprogramming.org/Perft
+ */
+
+/* Subtree counts keyed by position hash and depth, shared between
+ * threads. Each entry holds its key xored with its data so a torn
+ * write reads back as a miss instead of a wrong count
+ */
+pub struct PerftTable {
+    entries: Vec<(AtomicU64, AtomicU64)>,
+}
+
+impl PerftTable {
+    pub fn new(megabytes: usize) -> Self {
+        let len = ((megabytes << 20) / 16).max(1);
+        PerftTable { entries: (0..len).map(|_| (AtomicU64::new(0), AtomicU64::new(0))).collect() }
+    }
+
+    #[inline]
+    fn probe(&self, key: u64, depth: u32) -> Option<u64> {
+        let (check, data) = &self.entries[key as usize % self.entries.len()];
+        let (check, data) = (check.load(Relaxed), data.load(Relaxed));
+        if check ^ data == key && data & 0xff == depth as u64 {
+            return Some(data >> 8);
+        }
+        None
+    }
+
+    #[inline]
+    fn store(&self, key: u64, depth: u32, nodes: u64) {
+        let (check, data) = &self.entries[key as usize % self.entries.len()];
+        let value = nodes << 8 | depth as u64;
+        check.store(key ^ value, Relaxed);
+        data.store(value, Relaxed);
+    }
+}
+
+pub fn perft(game: &Game, depth: u32, table: Option<&PerftTable>) -> Result<u64, NotationError> {
+    if depth == 0 { return Ok(1); }
+    let moves = game.moves()?;
+    if depth == 1 { return Ok(moves.len() as u64); }
+
+    let key = table.map(|_| game.hash());
+    if let (Some(t), Some(key)) = (table, key) {
+        if let Some(nodes) = t.probe(key, depth) { return Ok(nodes); }
+    }
+
+    let mut nodes = 0;
+    for m in &moves {
+        let mut node = game.clone();
+        node.move_piece(*m.bits());
+        nodes += perft(&node, depth - 1, table)?;
+    }
+
+    if let (Some(t), Some(key)) = (table, key) {
+        t.store(key, depth, nodes);
+    }
+    Ok(nodes)
+}
+
+/* Node count below each root move by its UCI name, the root
+ * moves are split over the threads of the current rayon pool
+ */
+pub fn divide(game: &Game, depth: u32, table: Option<&PerftTable>) -> Result<Vec<(String, u64)>, NotationError> {
+    let moves = game.moves()?;
+    moves.par_iter().map(|m| {
+        let mut node = game.clone();
+        node.move_piece(*m.bits());
+        Ok((to_uci(game, m)?, perft(&node, depth.saturating_sub(1), table)?))
+    }).collect()
+}
+
+/* The breakdown of the leaf moves given on chessprogramming.org,
+ * as there a double check is not also counted as discovered
+ */
+#[derive(Default, Clone, Copy, Debug, PartialEq)]
+pub struct PerftStats {
+    pub nodes: u64,
+    pub captures: u64,
+    pub en_passant: u64,
+    pub castles: u64,
+    pub promotions: u64,
+    pub checks: u64,
+    pub discovered_checks: u64,
+    pub double_checks: u64,
+    pub checkmates: u64,
+}
+
+impl Add for PerftStats {
+    type Output = Self;
+
+    fn add(self, o: Self) -> Self {
+        PerftStats {
+            nodes: self.nodes + o.nodes,
+            captures: self.captures + o.captures,
+            en_passant: self.en_passant + o.en_passant,
+            castles: self.castles + o.castles,
+            promotions: self.promotions + o.promotions,
+            checks: self.checks + o.checks,
+            discovered_checks: self.discovered_checks + o.discovered_checks,
+            double_checks: self.double_checks + o.double_checks,
+            checkmates: self.checkmates + o.checkmates,
+        }
+    }
+}
+
+// classifies a leaf move from the boards before and after it
+fn leaf(game: &Game, node: &Game, mv: u64) -> Result<PerftStats, NotationError> {
+    let (us, them) = (game.turn, game.turn.opponent());
+    let taken = game.board.color(them) & !node.board.color(them);
+    let landed = node.board.color(us) & !game.board.color(us);
+    let pawns = |g: &Game| g.board.get(Kind::Pawn, us).count_ones();
+
+    let mut stats = PerftStats { nodes: 1, ..Default::default() };
+    stats.captures = (taken != 0) as u64;
+    stats.en_passant = (taken != 0 && taken & landed == 0) as u64;
+    stats.castles = castle::castle_move(game, &mv).is_some() as u64;
+    stats.promotions = (pawns(node) < pawns(game)) as u64;
+
+    // the move after has the checks and pins of the side now to move
+    let info = GameInfo::init(node)?;
+    if info.check {
+        stats.checks = 1;
+        stats.discovered_checks = (info.checkers & !landed != 0 && !info.double_check) as u64;
+        stats.double_checks = info.double_check as u64;
+        stats.checkmates = node.info()?.checkmate as u64;
+    }
+    Ok(stats)
+}
+
+// perft with every leaf move classified, no bulk counting
+pub fn perft_stats(game: &Game, depth: u32) -> Result<PerftStats, NotationError> {
+    if depth == 0 { return Ok(PerftStats { nodes: 1, ..Default::default() }); }
+    let moves = game.moves()?;
+    moves.par_iter().map(|m| {
+        let mut node = game.clone();
+        node.move_piece(*m.bits());
+        if depth == 1 { leaf(game, &node, *m.bits()) } else { perft_stats(&node, depth - 1) }
+    }).try_reduce(PerftStats::default, |a, b| Ok(a + b))
+}
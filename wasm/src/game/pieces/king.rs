@@ -1,24 +1,24 @@
 use super::{Piece, Pieces, Color};
-use crate::game::MASK;
+use crate::game::{attacks, board::*};
 
 #[derive(Clone)]
 pub struct King {
-    bits: u128,
+    bits: u64,
     color: Color,
 }
 
 impl King {
     pub fn new(x: usize, y: usize, color: Color) -> Self {
-        King { bits: 1 << (y << 4) + 8 + x, color }
+        King { bits: square(x, y), color }
     }
 
-    pub fn from_bits(bits: u128, color: Color) -> Self {
+    pub fn from_bits(bits: u64, color: Color) -> Self {
         King { bits, color }
     }
 }
 
 impl Piece for King {
-    fn bits(&self) -> &u128 {
+    fn bits(&self) -> &u64 {
         &self.bits
     }
 
@@ -26,11 +26,11 @@ impl Piece for King {
         &self.color
     }
 
-    fn set_bits(&mut self, bits: &u128) {
+    fn set_bits(&mut self, bits: &u64) {
         self.bits = *bits;
     }
 
-    fn moves(&self, opp: &u128, team: &u128, moves: &mut Vec<Pieces>) {
+    fn moves(&self, opp: &u64, team: &u64, moves: &mut Vec<Pieces>) {
         /* NOTE
          * opp MUST be the sum of all oppositision attacks
          * and teammates
@@ -38,106 +38,27 @@ impl Piece for King {
         let bits = &self.bits;
         let color = &self.color;
 
-        let mut test_move = |test: u128| {
-            if test & MASK & !(team | opp) == 0 { return; }
+        for test in Bits(attacks::king(index(*bits)) & !(team | opp)) {
             moves.push(Pieces::King(King { bits: test | bits, color: *color }));
-        };
-
-        /* North */
-        test_move(bits << 0x10);
-
-        /* South */
-        test_move(bits >> 0x10);
-
-        /* West */
-        test_move(bits << 0x01);
-
-        /* East */
-        test_move(bits >> 0x01);
-
-        /* Northwest */
-        test_move(bits << 0x11);
-
-        /* Southeast */
-        test_move(bits >> 0x11);
-
-        /* Northeast */
-        test_move(bits << 0x0f);
-
-        /* Southwest */
-        test_move(bits >> 0x0f);
+        }
     }
 
-    fn moves_as_bits(&self, opp: &u128, team: &u128, moves: &mut u128) {
+    fn moves_as_bits(&self, opp: &u64, team: &u64, moves: &mut u64) {
         /* NOTE
          * opp MUST be the sum of all oppositision attacks
          * and teammates
          */
-        let bits = &self.bits;
-
-        let mut test_move = |test: u128| {
-            if test & MASK & !(team | opp) != 0 { return; }
-            *moves |= test | bits;
-        };
-
-        /* North */
-        test_move(bits << 0x10);
-
-        /* South */
-        test_move(bits >> 0x10);
-
-        /* West */
-        test_move(bits << 0x01);
-
-        /* East */
-        test_move(bits >> 0x01);
-
-        /* Northwest */
-        test_move(bits << 0x11);
-
-        /* Southeast */
-        test_move(bits >> 0x11);
-
-        /* Northeast */
-        test_move(bits << 0x0f);
-
-        /* Southwest */
-        test_move(bits >> 0x0f);
+        let hits = attacks::king(index(self.bits)) & (team | opp);
+        if hits != 0 {
+            *moves |= hits | self.bits;
+        }
     }
 
-    fn moves_as_bits_exclusive(&self, opp: &u128, team: &u128, moves: &mut u128) {
+    fn moves_as_bits_exclusive(&self, opp: &u64, team: &u64, moves: &mut u64) {
         /* NOTE
          * opp MUST be the sum of all oppositision attacks
          * and teammates
          */
-        let bits = &self.bits;
-
-        let mut test_move = |test: u128| {
-            *moves |= test & MASK & !(team | opp);
-        };
-
-        /* North */
-        test_move(bits << 0x10);
-
-        /* South */
-        test_move(bits >> 0x10);
-
-        /* West */
-        test_move(bits << 0x01);
-
-        /* East */
-        test_move(bits >> 0x01);
-
-        /* Northwest */
-        test_move(bits << 0x11);
-
-        /* Southeast */
-        test_move(bits >> 0x11);
-
-        /* Northeast */
-        test_move(bits << 0x0f);
-
-        /* Southwest */
-        test_move(bits >> 0x0f);
+        *moves |= attacks::king(index(self.bits)) & !(team | opp);
     }
 }
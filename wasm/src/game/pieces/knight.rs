@@ -1,24 +1,29 @@
 use super::{Piece, Pieces, Color};
-use crate::game::MASK;
+use crate::game::{attacks, board::*};
 
 #[derive(Clone)]
 pub struct Knight {
-    bits: u128,
-    color: Color
+    bits: u64,
+    color: Color,
 }
 
 impl Knight {
     pub fn new(x: usize, y: usize, color: Color) -> Self {
-        Knight { bits: 1 << (y << 4) + 8 + x, color }
+        Knight { bits: square(x, y), color }
     }
 
-    pub fn from_bits(bits: u128, color: Color) -> Self {
+    pub fn from_bits(bits: u64, color: Color) -> Self {
         Knight { bits, color }
     }
+
+    #[inline]
+    fn attacks(&self, _occupied: u64) -> u64 {
+        attacks::knight(index(self.bits))
+    }
 }
 
 impl Piece for Knight {
-    fn bits(&self) -> &u128 {
+    fn bits(&self) -> &u64 {
         &self.bits
     }
 
@@ -26,66 +31,27 @@ impl Piece for Knight {
         &self.color
     }
 
-    fn set_bits(&mut self, bits: &u128) {
+    fn set_bits(&mut self, bits: &u64) {
         self.bits = *bits;
     }
 
-    fn moves(&self, _: &u128, team: &u128, moves: &mut Vec<Pieces>) {
+    fn moves(&self, opp: &u64, team: &u64, moves: &mut Vec<Pieces>) {
         let bits = &self.bits;
+        let color = &self.color;
 
-        let mut validate = |test: u128| {
-            if test & MASK & !team == 0 { return; }
-            moves.push(Pieces::Knight(Knight { bits: test | bits, color: self.color }));
-        };
-
-        let mut test_move = |x: u128| {
-            validate(bits << x);
-            validate(bits >> x);
-        };
-
-        // Knight magic numbers
-        test_move(0x21);
-        test_move(0x1f);
-        test_move(0x12);
-        test_move(0x0e);
+        for test in Bits(self.attacks(opp | team) & !team) {
+            moves.push(Pieces::Knight(Knight { bits: test | bits, color: *color }));
+        }
     }
 
-    fn moves_as_bits(&self, _: &u128, team: &u128, moves: &mut u128) {
-        let bits = &self.bits;
-
-        let mut validate = |test: u128| {
-            if test & MASK & !team == 0 { return; }
-            *moves |= test | bits;
-        };
-
-        let mut test_move = |x: u128| {
-            validate(bits << x);
-            validate(bits >> x);
-        };
-
-        // Knight magic numbers
-        test_move(0x21);
-        test_move(0x1f);
-        test_move(0x12);
-        test_move(0x0e);
+    fn moves_as_bits(&self, opp: &u64, team: &u64, moves: &mut u64) {
+        let targets = self.attacks(opp | team) & !team;
+        if targets != 0 {
+            *moves |= targets | self.bits;
+        }
     }
 
-    fn moves_as_bits_exclusive(&self, _: &u128, team: &u128, moves: &mut u128) {
-        let bits = &self.bits;
-
-        let mut validate = |test: u128| {
-            *moves |= test & MASK & !team;
-        };
-
-        let mut test_move = |x: u128| {
-            validate(bits << x);
-            validate(bits >> x);
-        };
-
-        // Knight magic numbers
-        test_move(0x21);
-        test_move(0x1f);
-        test_move(0x12);
-        test_move(0x0e);
+    fn moves_as_bits_exclusive(&self, opp: &u64, team: &u64, moves: &mut u64) {
+        *moves |= self.attacks(opp | team) & !team;
     }
 }
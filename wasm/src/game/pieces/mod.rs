@@ -13,12 +13,41 @@ pub use queen::Queen;
 pub mod king;
 pub use king::King;
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Color {
     White,
     Black,
 }
 
+impl Color {
+    #[inline]
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    #[inline]
+    pub fn opponent(self) -> Color {
+        match self {
+            Color::White => Color::Black,
+            Color::Black => Color::White,
+        }
+    }
+}
+
+// piece types in the same order as the Pieces variants,
+// used to index the board's bit boards
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Kind {
+    Pawn,
+    Bishop,
+    Knight,
+    Rook,
+    Queen,
+    King,
+}
+
+pub const KINDS: [Kind; 6] = [Kind::Pawn, Kind::Bishop, Kind::Knight, Kind::Rook, Kind::Queen, Kind::King];
+
 #[derive(Clone)]
 #[enum_dispatch(Piece)]
 pub enum Pieces {
@@ -30,13 +59,41 @@ pub enum Pieces {
     King(King),
 }
 
+impl Pieces {
+    pub fn from_kind(kind: Kind, bits: u64, color: Color) -> Self {
+        match kind {
+            Kind::Pawn => Pieces::Pawn(Pawn::from_bits(bits, color)),
+            Kind::Bishop => Pieces::Bishop(Bishop::from_bits(bits, color)),
+            Kind::Knight => Pieces::Knight(Knight::from_bits(bits, color)),
+            Kind::Rook => Pieces::Rook(Rook::from_bits(bits, color)),
+            Kind::Queen => Pieces::Queen(Queen::from_bits(bits, color)),
+            Kind::King => Pieces::King(King::from_bits(bits, color)),
+        }
+    }
+
+    #[inline]
+    pub fn kind(&self) -> Kind {
+        match self {
+            Pieces::Pawn(_) => Kind::Pawn,
+            Pieces::Bishop(_) => Kind::Bishop,
+            Pieces::Knight(_) => Kind::Knight,
+            Pieces::Rook(_) => Kind::Rook,
+            Pieces::Queen(_) => Kind::Queen,
+            Pieces::King(_) => Kind::King,
+        }
+    }
+}
+
+/* A piece, or a move of that piece when its bits hold
+ * both the source and destination squares
+ */
 #[enum_dispatch]
 pub trait Piece {
-    fn bits(&self) -> &u128;
-    fn set_bits(&mut self, bits: &u128);
+    fn bits(&self) -> &u64;
+    fn set_bits(&mut self, bits: &u64);
     fn color(&self) -> &Color;
-    fn moves(&self, opp: &u128, team: &u128, moves: &mut Vec<Pieces>);
-    fn moves_as_bits(&self, opp: &u128, team: &u128, moves: &mut u128);
+    fn moves(&self, opp: &u64, team: &u64, moves: &mut Vec<Pieces>);
+    fn moves_as_bits(&self, opp: &u64, team: &u64, moves: &mut u64);
     // does not add piece.bits() to moves bit board
-    fn moves_as_bits_exclusive(&self, opp: &u128, team: &u128, moves: &mut u128);
+    fn moves_as_bits_exclusive(&self, opp: &u64, team: &u64, moves: &mut u64);
 }
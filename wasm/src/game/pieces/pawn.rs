@@ -1,49 +1,36 @@
 use super::{Piece, Pieces, Color};
-use crate::{game::MASK, game::util::promote};
+use crate::game::{attacks, board::*, util::promote};
 
 #[derive(Clone)]
 pub struct Pawn {
-    bits: u128,
+    bits: u64,
     color: Color,
 }
 
 impl Pawn {
     pub fn new(x: usize, y: usize, color: Color) -> Self {
-        Pawn { bits: 1 << (y << 4) + 8 + x, color }
+        Pawn { bits: square(x, y), color }
     }
 
-    pub fn from_bits(bits: u128, color: Color) -> Self {
+    pub fn from_bits(bits: u64, color: Color) -> Self {
         Pawn { bits, color }
     }
 
     #[inline]
-    pub fn attacks(&self, attacks: &mut u128) {
+    pub fn attacks(&self, attacks: &mut u64) {
         let bits = &self.bits;
         let color = &self.color;
 
-        let mut validate = |test: u128| {
-            *attacks |= test & MASK;
-        };
-
-        match color {
-            Color::White => {
-                validate(bits << 0x0f);
-                validate(bits << 0x11);
-            }
-            Color::Black => {
-                validate(bits >> 0x0f);
-                validate(bits >> 0x11);
-            }
-        }
+        *attacks |= attacks::pawn(*color, index(*bits));
     }
 }
 
 impl Piece for Pawn {
-    fn bits(&self) -> &u128 {
+    fn bits(&self) -> &u64 {
         &self.bits
     }
 
-    fn set_bits(&mut self, bits: &u128) {
+    fn set_bits(&mut self, bits: &u64) {
         self.bits = *bits;
     }
 
@@ -51,112 +38,112 @@ impl Piece for Pawn {
         &self.color
     }
 
-    fn moves(&self, opp: &u128, team: &u128, moves: &mut Vec<Pieces>) {
+    fn moves(&self, opp: &u64, team: &u64, moves: &mut Vec<Pieces>) {
         let bits = &self.bits;
         let color = &self.color;
 
-        let validate_attack = |test: u128, moves: &mut Vec<Pieces>| {
-            if test & MASK & opp == 0 { return; }
+        let validate_attack = |test: u64, moves: &mut Vec<Pieces>| {
+            if test & opp == 0 { return; }
             let mv = Pieces::Pawn(Pawn { bits: test | bits, color: *color});
             promote::add_promotions(&mv , moves);
             moves.push(mv);
         };
 
-        let validate_move = |test: u128, moves: &mut Vec<Pieces>| -> bool {
-            if test & MASK & !team & !opp == 0 { return false; }
+        let validate_move = |test: u64, moves: &mut Vec<Pieces>| -> bool {
+            if test & !team & !opp == 0 { return false; }
             let mv = Pieces::Pawn(Pawn { bits: test | bits, color: *color});
             promote::add_promotions(&mv , moves);
             moves.push(mv);
             return true;
         };
 
+        for test in Bits(attacks::pawn(*color, index(*bits))) {
+            validate_attack(test, moves);
+        }
+
         match color {
             Color::White => {
-                validate_attack(bits << 0x0f, moves);
-                validate_attack(bits << 0x11, moves);
-                let valid = validate_move(bits << 0x10, moves);
-                if valid && bits >> 0x18 & 0xff != 0 {
-                    validate_move(bits << 0x20, moves);
+                let valid = validate_move(north(*bits), moves);
+                if valid && bits & RANK_2 != 0 {
+                    validate_move(bits << 0x10, moves);
                 }
             }
             Color::Black => {
-                validate_attack(bits >> 0x0f, moves);
-                validate_attack(bits >> 0x11, moves);
-                let valid = validate_move(bits >> 0x10, moves);
-                if valid && bits >> 0x68 & 0xff != 0 {
-                    validate_move(bits >> 0x20, moves);
+                let valid = validate_move(south(*bits), moves);
+                if valid && bits & RANK_7 != 0 {
+                    validate_move(bits >> 0x10, moves);
                 }
             }
         }
     }
 
-    fn moves_as_bits(&self, opp: &u128, team: &u128, moves: &mut u128) {
+    fn moves_as_bits(&self, opp: &u64, team: &u64, moves: &mut u64) {
         let bits = &self.bits;
         let color = &self.color;
 
-        let validate_attack = |test: u128, moves: &mut u128| {
-            if test & MASK & opp != 0 {
+        let validate_attack = |test: u64, moves: &mut u64| {
+            if test & opp != 0 {
                 *moves |= test | bits;
             }
         };
 
-        let validate_move = |test: u128, moves: &mut u128| -> bool {
-            if test & MASK & !team & !opp == 0 { return false; }
+        let validate_move = |test: u64, moves: &mut u64| -> bool {
+            if test & !team & !opp == 0 { return false; }
             *moves |= test | bits;
             return true;
         };
 
+        for test in Bits(attacks::pawn(*color, index(*bits))) {
+            validate_attack(test, moves);
+        }
+
         match color {
             Color::White => {
-                validate_attack(bits << 0x0f, moves);
-                validate_attack(bits << 0x11, moves);
-                let valid = validate_move(bits << 0x10, moves);
-                if valid && bits >> 0x18 & 0xff != 0 {
-                    validate_move(bits << 0x20, moves);
+                let valid = validate_move(north(*bits), moves);
+                if valid && bits & RANK_2 != 0 {
+                    validate_move(bits << 0x10, moves);
                 }
             }
             Color::Black => {
-                validate_attack(bits >> 0x0f, moves);
-                validate_attack(bits >> 0x11, moves);
-                let valid = validate_move(bits >> 0x10, moves);
-                if valid && bits >> 0x68 & 0xff != 0 {
-                    validate_move(bits >> 0x20, moves);
+                let valid = validate_move(south(*bits), moves);
+                if valid && bits & RANK_7 != 0 {
+                    validate_move(bits >> 0x10, moves);
                 }
             }
         }
     }
 
-    fn moves_as_bits_exclusive(&self, opp: &u128, team: &u128, moves: &mut u128) {
+    fn moves_as_bits_exclusive(&self, opp: &u64, team: &u64, moves: &mut u64) {
         let bits = &self.bits;
         let color = &self.color;
 
-        let validate_attack = |test: u128, moves: &mut u128| {
-            if test & MASK & opp != 0 {
+        let validate_attack = |test: u64, moves: &mut u64| {
+            if test & opp != 0 {
                 *moves |= test;
             }
         };
 
-        let validate_move = |test: u128, moves: &mut u128| -> bool {
-            if test & MASK & !team & !opp == 0 { return false; }
+        let validate_move = |test: u64, moves: &mut u64| -> bool {
+            if test & !team & !opp == 0 { return false; }
             *moves |= test;
             return true;
         };
 
+        for test in Bits(attacks::pawn(*color, index(*bits))) {
+            validate_attack(test, moves);
+        }
+
         match color {
             Color::White => {
-                validate_attack(bits << 0x0f, moves);
-                validate_attack(bits << 0x11, moves);
-                let valid = validate_move(bits << 0x10, moves);
-                if valid && bits >> 0x18 & 0xff != 0 {
-                    validate_move(bits << 0x20, moves);
+                let valid = validate_move(north(*bits), moves);
+                if valid && bits & RANK_2 != 0 {
+                    validate_move(bits << 0x10, moves);
                 }
             }
             Color::Black => {
-                validate_attack(bits >> 0x0f, moves);
-                validate_attack(bits >> 0x11, moves);
-                let valid = validate_move(bits >> 0x10, moves);
-                if valid && bits >> 0x68 & 0xff != 0 {
-                    validate_move(bits >> 0x20, moves);
+                let valid = validate_move(south(*bits), moves);
+                if valid && bits & RANK_7 != 0 {
+                    validate_move(bits >> 0x10, moves);
                 }
             }
         }
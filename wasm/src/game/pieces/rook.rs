@@ -1,34 +1,29 @@
 use super::{Piece, Pieces, Color};
-use crate::game::MASK;
+use crate::game::{attacks, board::*};
 
 #[derive(Clone)]
 pub struct Rook {
-    bits: u128,
+    bits: u64,
     color: Color,
 }
 
 impl Rook {
     pub fn new(x: usize, y: usize, color: Color) -> Self {
-        Rook { bits: 1 << (y << 4) + 8 + x, color }
+        Rook { bits: square(x, y), color }
     }
 
-    pub fn from_bits(bits: u128, color: Color) -> Self {
+    pub fn from_bits(bits: u64, color: Color) -> Self {
         Rook { bits, color }
     }
 
     #[inline]
-    fn test_move<F, G, T>(&self, condition: F, validation: G, moves: &mut T)
-        where F: Fn(i32) -> u128, G: Fn(&u128, &mut T) -> bool {
-
-            for i in 1..8 {
-                let test = condition(i);
-                if !validation(&test, moves) { break; }
-            }
-        }
+    fn attacks(&self, occupied: u64) -> u64 {
+        attacks::rook(index(self.bits), occupied)
+    }
 }
 
 impl Piece for Rook {
-    fn bits(&self) -> &u128 {
+    fn bits(&self) -> &u64 {
         &self.bits
     }
 
@@ -36,77 +31,27 @@ impl Piece for Rook {
         &self.color
     }
 
-    fn set_bits(&mut self, bits: &u128) {
+    fn set_bits(&mut self, bits: &u64) {
         self.bits = *bits;
     }
 
-    fn moves(&self, opp: &u128, team: &u128, moves: &mut Vec<Pieces>) {
+    fn moves(&self, opp: &u64, team: &u64, moves: &mut Vec<Pieces>) {
         let bits = &self.bits;
-        let color = self.color();
-
-        let validate = |test: &u128, moves: &mut Vec<Pieces>| -> bool {
-            if test & MASK & !team == 0 { return false; }
-            if test & opp  != 0 { moves.push(Pieces::Rook(Rook { bits: *test | bits, color: *color })); return false; }
-            moves.push(Pieces::Rook(Rook { bits: *test | bits, color: *color }));
-            return true;
-        };
-
-        /* North */
-        self.test_move(|i: i32| { bits << (i << 4) }, validate, moves);
-
-        /* South */
-        self.test_move(|i: i32| { bits >> (i << 4) }, validate, moves);
+        let color = &self.color;
 
-        /* West */
-        self.test_move(|i: i32| { bits << i }, validate, moves);
-
-        /* East */
-        self.test_move(|i: i32| { bits >> i }, validate, moves);
+        for test in Bits(self.attacks(opp | team) & !team) {
+            moves.push(Pieces::Rook(Rook { bits: test | bits, color: *color }));
+        }
     }
 
-    fn moves_as_bits(&self, opp: &u128, team: &u128, moves: &mut u128) {
-        let bits = &self.bits;
-
-        let validate = |test: &u128, moves: &mut u128| -> bool {
-            if test & MASK & !team == 0 { return false; }
-            if test & opp  != 0 { *moves |= *test | bits; return false; }
-            *moves |= *test | bits;
-            return true;
-        };
-
-        /* North */
-        self.test_move(|i: i32| { bits << (i << 4) }, validate, moves);
-
-        /* South */
-        self.test_move(|i: i32| { bits >> (i << 4) }, validate, moves);
-
-        /* West */
-        self.test_move(|i: i32| { bits << i }, validate, moves);
-
-        /* East */
-        self.test_move(|i: i32| { bits >> i }, validate, moves);
+    fn moves_as_bits(&self, opp: &u64, team: &u64, moves: &mut u64) {
+        let targets = self.attacks(opp | team) & !team;
+        if targets != 0 {
+            *moves |= targets | self.bits;
+        }
     }
 
-    fn moves_as_bits_exclusive(&self, opp: &u128, team: &u128, moves: &mut u128) {
-        let bits = &self.bits;
-
-        let validate = |test: &u128, moves: &mut u128| -> bool {
-            if test & MASK & !team == 0 { return false; }
-            if test & opp  != 0 { *moves |= *test; return false; }
-            *moves |= *test;
-            return true;
-        };
-
-        /* North */
-        self.test_move(|i: i32| { bits << (i << 4) }, validate, moves);
-
-        /* South */
-        self.test_move(|i: i32| { bits >> (i << 4) }, validate, moves);
-
-        /* West */
-        self.test_move(|i: i32| { bits << i }, validate, moves);
-
-        /* East */
-        self.test_move(|i: i32| { bits >> i }, validate, moves);
+    fn moves_as_bits_exclusive(&self, opp: &u64, team: &u64, moves: &mut u64) {
+        *moves |= self.attacks(opp | team) & !team;
     }
 }
@@ -1,109 +1,195 @@
-use crate::game::pieces::{
-    Color,
-    Color::White,
-    Color::Black,
-    Piece,
-    Pieces,
-    King,
+use crate::game::{
+    Game,
+    attacks,
+    board::*,
+    pieces::{
+        Color,
+        Color::White,
+        Color::Black,
+        Kind,
+        Piece,
+        Pieces,
+        King,
+    },
 };
+use super::GameInfo;
 
 #[allow(non_upper_case_globals)]
 pub mod constants
 {
-    // These are the location of the king and rook squares
-    // used to detect if they moved, so we can take away their
-    // castling rights
-    pub const K_SQUARES: u128 = 0x90 << 0x08; // ....x..x
-    pub const Q_SQUARES: u128 = 0x11 << 0x08; // x...x...
-    pub const k_SQUARES: u128 = 0x90 << 0x78; // ....x..x
-    pub const q_SQUARES: u128 = 0x11 << 0x78; // x...x...
-
-    // This is the move the king maskes when castling
-    // on each side of the baord
-    // used in both user input and when the king moves
-    pub const K_MOVE: u128 = 0x50 << 0x08; // ....x.x.
-    pub const Q_MOVE: u128 = 0x14 << 0x08; // ..x.x...
-    pub const k_MOVE: u128 = 0x50 << 0x78; // ....x.x.
-    pub const q_MOVE: u128 = 0x14 << 0x78; // ..x.x...
-
-    // corresponding rook castling move
-    pub const K_ROOK: u128 = 0xa0 << 0x08; // .....x.x
-    pub const Q_ROOK: u128 = 0x09 << 0x08; // x..x....
-    pub const k_ROOK: u128 = 0xa0 << 0x78; // .....x.x
-    pub const q_ROOK: u128 = 0x09 << 0x78; // x..x....
-
-    // used to distinguish a castle in move list from
-    // normal move, hits both king and rook
-    pub const K_ZONE: u128 = 0xf0 << 0x08; // ....xxxx
-    pub const Q_ZONE: u128 = 0x1f << 0x08; // xxxxx...
-    pub const k_ZONE: u128 = 0xf0 << 0x78; // ....xxxx
-    pub const q_ZONE: u128 = 0x1f << 0x78; // xxxxx...
-
-    // used to determine if space between caslting
-    // is empty and not under attack
-    pub const K_VALID: u128 = 0x60 << 0x08; // .....xx.
-    pub const Q_VALID: u128 = 0x0e << 0x08; // .xxx....
-    pub const k_VALID: u128 = 0x60 << 0x78; // .....xx.
-    pub const q_VALID: u128 = 0x0e << 0x78; // .xxx....
-
-    // Castling is still possible when b8 and b1
-    // are under attack by opposing pieces, hence
-    // edge case
-    pub const EDGE_CASE: u128 = (0x02 << 0x08) | (0x02 << 0x78);
+    // This is the move the king makes when castling
+    // on each side of the board in standard chess,
+    // used for user input
+    pub const K_MOVE: u64 = 0x50;        // ....x.x.
+    pub const Q_MOVE: u64 = 0x14;        // ..x.x...
+    pub const k_MOVE: u64 = 0x50 << 0x38; // ....x.x.
+    pub const q_MOVE: u64 = 0x14 << 0x38; // ..x.x...
 
     // used to identify castling in fen string
     pub const K_ID: u16 = 0x0001;
     pub const Q_ID: u16 = 0x0010;
     pub const k_ID: u16 = 0x0100;
     pub const q_ID: u16 = 0x1000;
+    pub const IDS: [u16; 4] = [K_ID, Q_ID, k_ID, q_ID];
+
+    // rook files the rights refer to in standard chess
+    pub const DEFAULT_ROOKS: [u8; 4] = [7, 0, 7, 0];
+
+    // files the king and rook land on, king side then queen side
+    pub const KING_TO: [u8; 2] = [6, 2];
+    pub const ROOK_TO: [u8; 2] = [5, 3];
 }
 pub use constants::*;
 
+/* A castle is encoded in the move list as the king's
+ * square together with its rook's square, the king
+ * "takes" its own rook. This works for any Chess960
+ * setup, including the king or rook not moving at all
+ */
+pub struct Castle {
+    pub right: usize, // index into IDS
+    pub king_from: u64,
+    pub king_to: u64,
+    pub rook_from: u64,
+    pub rook_to: u64,
+}
+
+// bit offset of the a-file on a color's back rank
 #[inline]
-pub fn add_castling(castling: u16, obstacles: &u128, color: Color, moves: &mut Vec<Pieces>) {
-    if castling == 0 { return; }
-    if color == White {
-        if K_ID & castling != 0 && K_VALID & obstacles == 0 {
-            moves.push(Pieces::King(King::from_bits(K_ZONE, White)));
-        }
-        if Q_ID & castling != 0 && Q_VALID & obstacles == 0 {
-            moves.push(Pieces::King(King::from_bits(Q_ZONE, White)));
-        }
-    } else { // Black
-        if k_ID & castling != 0 && k_VALID & obstacles == 0 {
-            moves.push(Pieces::King(King::from_bits(k_ZONE, Black)));
-        }
-        if q_ID & castling != 0 && q_VALID & obstacles == 0 {
-            moves.push(Pieces::King(King::from_bits(q_ZONE, Black)));
+fn back_rank_shift(color: Color) -> u8 {
+    match color {
+        White => 0x00,
+        Black => 0x38,
+    }
+}
+
+// every square from a to b inclusive, both on the same rank
+#[inline]
+fn span(a: u64, b: u64) -> u64 {
+    let (lo, hi) = if a < b { (a, b) } else { (b, a) };
+    (hi - lo) | hi
+}
+
+#[inline]
+fn right_color(right: usize) -> Color {
+    if right < 2 { White } else { Black }
+}
+
+impl Castle {
+    pub fn new(right: usize, king_file: u8, rook_file: u8) -> Self {
+        let shift = back_rank_shift(right_color(right));
+        Castle {
+            right,
+            king_from: 1 << (shift + king_file),
+            king_to: 1 << (shift + KING_TO[right % 2]),
+            rook_from: 1 << (shift + rook_file),
+            rook_to: 1 << (shift + ROOK_TO[right % 2]),
         }
     }
+
+    // squares the king and rook cross, these must be empty
+    // apart from the castling king and rook themselves
+    #[inline]
+    pub fn path(&self) -> u64 {
+        span(self.king_from, self.king_to) | span(self.rook_from, self.rook_to)
+    }
+}
+
+// files of a color's king and rooks standing on its back rank
+pub fn back_rank(board: &Board, color: Color) -> (Option<u8>, Vec<u8>) {
+    let rank = RANK_1 << back_rank_shift(color);
+    let file = |bits: u64| (index(bits) % 8) as u8;
+    let king = board.get(Kind::King, color) & rank;
+    let rooks = Bits(board.get(Kind::Rook, color) & rank).map(file).collect();
+    ((king != 0).then(|| file(king)), rooks)
+}
+
+// the castle available under a right, if the king and
+// the rook it refers to are still on their squares
+pub fn castle(game: &Game, right: usize) -> Option<Castle> {
+    if game.castling & IDS[right] == 0 { return None; }
+    let color = right_color(right);
+    let (king, rooks) = back_rank(&game.board, color);
+    let rook = game.castling_rooks[right];
+    if !rooks.contains(&rook) { return None; }
+    Some(Castle::new(right, king?, rook))
+}
+
+// the castle a move from the move list stands for, if any
+pub fn castle_move(game: &Game, mv: &u64) -> Option<Castle> {
+    if game.castling == 0 || mv.count_ones() != 2 { return None; }
+    let offset = if game.turn == White { 0 } else { 2 };
+    (offset..offset + 2)
+        .filter_map(|right| castle(game, right))
+        .find(|c| c.king_from | c.rook_from == *mv)
+}
+
+// user input in standard chess moves the king two squares,
+// translate it to the king-takes-rook encoding
+pub fn from_king_move(game: &Game, mv: &u64) -> Option<u64> {
+    if game.chess960 { return None; }
+    let right = [K_MOVE, Q_MOVE, k_MOVE, q_MOVE].iter().position(|m| m == mv)?;
+    castle(game, right)
+        .filter(|c| c.king_from | c.king_to == *mv)
+        .map(|c| c.king_from | c.rook_from)
 }
 
 #[inline]
-pub fn try_castle (piece: &mut Pieces, king_move: u128, rook_move: u128) {
-    if let Pieces::King(k) = piece {
-        k.set_bits(&(k.bits() ^ king_move));
-    } else if let Pieces::Rook(r) = piece {
-        r.set_bits(&(r.bits() ^ rook_move));
+pub fn add_castling(game: &Game, info: &GameInfo, moves: &mut Vec<Pieces>) {
+    if game.castling == 0 { return; }
+    let offset = if game.turn == White { 0 } else { 2 };
+    let occupied = info.team_pieces | info.opp_pieces;
+
+    for right in offset..offset + 2 {
+        let c = match castle(game, right) {
+            Some(c) if c.king_from == *info.king.bits() => c,
+            _ => continue,
+        };
+
+        let movers = c.king_from | c.rook_from;
+        if c.path() & occupied & !movers != 0 { continue; }
+
+        // the king may not pass through or land on an attacked square
+        if span(c.king_from, c.king_to) & info.opp_attacks != 0 { continue; }
+
+        // the castling rook may have been shielding the king's
+        // destination from a rook or queen on the back rank
+        let after = (info.team_pieces & !movers) | c.king_to | c.rook_to;
+        let rays = attacks::rook(index(c.king_to), after | info.opp_pieces);
+        if rays & info.opp_straight != 0 { continue; }
+
+        moves.push(Pieces::King(King::from_bits(movers, game.turn)));
     }
 }
 
+// moves the king and rook to their castled squares
+pub fn try_castle(game: &mut Game, c: &Castle) {
+    game.board.toggle(Kind::King, game.turn, c.king_from ^ c.king_to);
+    game.board.toggle(Kind::Rook, game.turn, c.rook_from ^ c.rook_to);
+    game.castling = remove_rights(game.castling, game.turn);
+    game.en_passant_square = 0;
+}
+
+// a king move gives up both of its color's rights
 #[inline]
 #[must_use]
-pub fn fix_castle (mut castling: u16, mv: &u128) -> u16 {
-    // if branch structured by have the diagonals as 
-    // else ifs for performance reasons, possible negligable
-    if *mv & K_SQUARES != 0 {
-        castling &= !K_ID;
-    }
-    else if *mv & q_SQUARES != 0 {
-        castling &= !q_ID;
-    }
-    if *mv & Q_SQUARES != 0 {
-        castling &= !Q_ID;
+pub fn remove_rights(castling: u16, color: Color) -> u16 {
+    match color {
+        White => castling & !(K_ID | Q_ID),
+        Black => castling & !(k_ID | q_ID),
     }
-    else if *mv & k_SQUARES != 0 {
-        castling &= !k_ID;
+}
+
+// a move touching a right's rook square, by moving
+// or capturing the rook, gives up that right
+#[inline]
+#[must_use]
+pub fn fix_castle(mut castling: u16, rooks: &[u8; 4], mv: &u64) -> u16 {
+    for (right, id) in IDS.iter().enumerate() {
+        let rook: u64 = 1 << (back_rank_shift(right_color(right)) + rooks[right]);
+        if mv & rook != 0 {
+            castling &= !id;
+        }
     }
-    return castling;
+    castling
 }
@@ -1,137 +1,80 @@
-use std::error::Error;
 use crate::game::{
+    attacks,
+    board::{Bits, index},
     pieces::{
         Color::*,
+        Kind,
         Piece,
-        Pieces,
-        Bishop,
-        Knight,
-        Rook,
-        Queen,
     },
     Game,
     GameError,
 };
 use super::GameInfo;
 
-// removes moves that are moving a pinned peice
-// returns bool to be used in filter method
-#[inline]
-pub fn filter_pins(info: &GameInfo, game: &Game, mv: &u128) -> bool {
-    let diagonal = Pieces::Bishop(Bishop::from_bits(*info.king.bits(), game.turn));
-    let straight = Pieces::Rook(Rook::from_bits(*info.king.bits(), game.turn));
-
-    let en_passant;
-    match game.turn {
-        White => en_passant = (game.en_passant_square & mv) >> 0x10,
-        Black => en_passant = (game.en_passant_square & mv) << 0x10,
+/* Finds the pieces checking the king and the team pieces
+ * pinned to him, sliders are looked up from the king's
+ * square seeing through the team to find the pinners
+ */
+pub fn find_checks(info: &mut GameInfo, game: &Game) -> Result<(), GameError> {
+    let them = game.turn.opponent();
+    let king = index(*info.king.bits());
+    let occupied = info.team_pieces | info.opp_pieces;
+
+    if attacks::king(king) & game.board.get(Kind::King, them) != 0 {
+        return Err(GameError::KingGivesCheck);
     }
 
-    let test_diagonal = info.opp_diagonal & !mv;
-    let test_straight = info.opp_straight & !mv;
-
-    let mut diagonal_moves = 0u128;
-    diagonal.moves_as_bits(&(info.opp_pieces & !en_passant), &(info.team_pieces ^ mv), &mut diagonal_moves);
-    if diagonal_moves & test_diagonal != 0 { return false; }
-
-    let mut straight_moves = 0u128;
-    straight.moves_as_bits(&(info.opp_pieces & !en_passant), &(info.team_pieces ^ mv), &mut straight_moves);
-    if straight_moves & test_straight != 0 { return false; }
+    info.checkers = attacks::knight(king) & game.board.get(Kind::Knight, them)
+        | attacks::pawn(game.turn, king) & game.board.get(Kind::Pawn, them)
+        | attacks::bishop(king, occupied) & info.opp_diagonal
+        | attacks::rook(king, occupied) & info.opp_straight;
+    info.check = info.checkers != 0;
+    info.double_check = info.checkers.count_ones() > 1;
+
+    let pinners = attacks::bishop(king, info.opp_pieces) & info.opp_diagonal
+        | attacks::rook(king, info.opp_pieces) & info.opp_straight;
+    for pinner in Bits(pinners) {
+        let blockers = attacks::between(king, index(pinner)) & occupied;
+        if blockers.count_ones() == 1 && blockers & info.team_pieces != 0 {
+            info.pinned |= blockers;
+        }
+    }
 
-    return true;
+    Ok(())
 }
 
-pub fn gen_check_moves(info: &mut GameInfo, game: &Game, moves: &mut Vec<Pieces>) -> Result<(), Box<dyn Error>> {
-    let mut king_rays = 0u128;
-
-    let king_ray_maker = Pieces::Queen(Queen::from_bits(*info.king.bits(), game.turn));
-    king_ray_maker.moves_as_bits(&info.opp_pieces, &info.team_pieces, &mut king_rays);
-
-    let king_ray_maker = Pieces::Knight(Knight::from_bits(*info.king.bits(), game.turn));
-    king_ray_maker.moves_as_bits(&info.opp_pieces, &info.team_pieces, &mut king_rays);
-
-    let mut attacker: Option<&Pieces> = None;
-    let mut attack = 0u128;
-    for piece in &game.pieces {
-        let mut bits = 0u128;
-        if game.turn != *piece.color() && piece.bits() & king_rays != 0 {
-            piece.moves_as_bits(&info.team_pieces, &info.opp_pieces, &mut bits);
-            if bits & info.king.bits() != 0 {
-                if let None = attacker {
-                    attacker = Some(piece);
-                    attack = bits;
-                } else {
-                    // if attacker already assigned,
-                    // it's double-check and only king moves
-                    info.double_check = true;
-                    return Ok(());
-                }
-            }
-        }
+// squares a non-king move must land on, capturing or blocking the checker
+#[inline]
+pub fn check_mask(info: &GameInfo) -> u64 {
+    match info.checkers.count_ones() {
+        0 => !0,
+        1 => attacks::between(index(*info.king.bits()), index(info.checkers)) | info.checkers,
+        _ => 0,
     }
+}
 
-    let mut rays;
-    /* Gen moves for king as if another peice and & them to
-     * the attackers moves to determine the line of attack
-     * allows for pieces to block sight of attacker
-     */
-    let calc_rays = |piece_map: &Pieces, attack: &u128| {
-        let mut piece_rays = 0u128;
-        piece_map.moves_as_bits(&info.opp_pieces, &info.team_pieces, &mut piece_rays);
-        return piece_rays & attack;
-    };
+// a pinned piece may only move along the line through the king
+#[inline]
+pub fn pin_mask(info: &GameInfo, from: u64) -> u64 {
+    if from & info.pinned == 0 { return !0; }
+    attacks::line(index(*info.king.bits()), index(from))
+}
 
-    let attacker = match attacker {
-        Some(atk) => atk,
-        None => { return Err(Box::new(GameError("Attack value of None".into()))); },
+/* En passant takes two pieces off a rank at once, so the
+ * board after the capture is checked for sliders directly
+ */
+pub fn en_passant_safe(info: &GameInfo, game: &Game, from: u64, to: u64) -> bool {
+    let captured = match game.turn {
+        White => to >> 0x08,
+        Black => to << 0x08,
     };
+    let king = index(*info.king.bits());
+    let occupied = (info.team_pieces ^ from | to) | (info.opp_pieces & !captured);
 
-    match attacker {
-        Pieces::Pawn(_) |
-        Pieces::Bishop(_) => {
-            let king_map = Pieces::Bishop(Bishop::from_bits(*info.king.bits(), game.turn));
-            rays = calc_rays(&king_map, &attack);
-        },
-        Pieces::Knight(_) => {
-            let king_map = Pieces::Knight(Knight::from_bits(*info.king.bits(), game.turn));
-            rays = calc_rays(&king_map, &attack);
-        },
-        Pieces::Rook(_) => {
-            let king_map = Pieces::Rook(Rook::from_bits(*info.king.bits(), game.turn));
-            rays = calc_rays(&king_map, &attack);
-        },
-        Pieces::Queen(q) => {
-            attack = 0u128;
-            let piece = Pieces::Bishop(Bishop::from_bits(*q.bits(), game.turn));
-            piece.moves_as_bits(&info.team_pieces, &info.opp_pieces, &mut attack);
-
-            let king_map = Pieces::Bishop(Bishop::from_bits(*info.king.bits(), game.turn));
-            rays = calc_rays(&king_map, &attack);
-
-            // check if queen is attacking on diagonal, if not use rook attacks
-            if rays & (info.king.bits() | q.bits()) != info.king.bits() | q.bits() {
-                attack = 0u128;
-                let piece = Pieces::Rook(Rook::from_bits(*q.bits(), game.turn));
-                piece.moves_as_bits(&info.team_pieces, &info.opp_pieces, &mut attack);
-
-                let king_map = Pieces::Rook(Rook::from_bits(*info.king.bits(), game.turn));
-                rays = calc_rays(&king_map, &attack);
-            }
-        },
-        Pieces::King(_) => panic!("King should not be checking another king"),
-    }
-
-    for piece in &game.pieces {
-        if game.turn == *piece.color() {
-            if let Pieces::King(_) = piece { continue; }
-
-            let mut piece_moves = Vec::with_capacity(32);
-            piece.moves(&info.opp_pieces, &info.team_pieces, &mut piece_moves);
-            for mv in piece_moves {
-                if mv.bits() & rays != 0 { moves.push(mv); }
-            }
-        }
-    }
+    // a knight or another pawn giving check is not answered
+    let leapers = info.checkers & !captured & !(info.opp_diagonal | info.opp_straight);
+    if leapers != 0 { return false; }
 
-    Ok(())
+    attacks::bishop(king, occupied) & info.opp_diagonal == 0
+        && attacks::rook(king, occupied) & info.opp_straight == 0
 }
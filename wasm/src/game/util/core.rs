@@ -1,24 +1,62 @@
 use crate::game::{
     Game,
-    util::GameInfo,
+    board::Bits,
+    util::{
+        GameInfo,
+        check,
+        promote,
+    },
     pieces::{
+        Kind,
+        KINDS,
         Piece,
         Pieces,
     },
 };
 
-pub fn gen_moves(info: &mut GameInfo, game: &Game, moves: &mut Vec<Pieces>) {
-    for piece in &game.pieces {
-        if game.turn == *piece.color() {
-            match piece {
-                Pieces::King(_) => (),
-                Pieces::Pawn(p) => {
-                    let opp = info.opp_pieces | game.en_passant_square;
-                    p.moves(&opp, &info.team_pieces, moves);
-                },
-                _ => piece.moves(&info.opp_pieces, &info.team_pieces, moves),
+/* Only legal moves are added, the targets of each piece
+ * are cut down to its pin line and to the squares that
+ * answer a check before any move is made from them
+ */
+pub fn gen_moves(info: &GameInfo, game: &Game, moves: &mut Vec<Pieces>) {
+    if info.double_check { return; }
+    let check_mask = check::check_mask(info);
+
+    for kind in KINDS {
+        if kind == Kind::King { continue; }
+        for from in Bits(game.board.get(kind, game.turn)) {
+            let piece = Pieces::from_kind(kind, from, game.turn);
+
+            let mut targets = 0u64;
+            let mut en_passant = 0u64;
+            if kind == Kind::Pawn {
+                let opp = info.opp_pieces | game.en_passant_square;
+                piece.moves_as_bits_exclusive(&opp, &info.team_pieces, &mut targets);
+                en_passant = targets & game.en_passant_square;
+            } else {
+                piece.moves_as_bits_exclusive(&info.opp_pieces, &info.team_pieces, &mut targets);
+            }
+            targets &= check_mask & check::pin_mask(info, from) & !en_passant;
+
+            if en_passant != 0 && check::en_passant_safe(info, game, from, en_passant) {
+                targets |= en_passant;
             }
-        } else { // Opp
+
+            for to in Bits(targets) {
+                let mv = Pieces::from_kind(kind, from | to, game.turn);
+                if kind == Kind::Pawn {
+                    promote::add_promotions(&mv, moves);
+                }
+                moves.push(mv);
+            }
+        }
+    }
+}
+
+// opp attacks see through the king so he cannot step back along a checking ray
+pub fn gen_attacks(info: &mut GameInfo, game: &Game) {
+    for piece in game.pieces() {
+        if game.turn != *piece.color() {
             match piece {
                 Pieces::Pawn(p) => {
                     p.attacks(&mut info.opp_attacks);
@@ -26,27 +64,23 @@ pub fn gen_moves(info: &mut GameInfo, game: &Game, moves: &mut Vec<Pieces>) {
                 Pieces::King(k) => {
                     // enemy king should never come near king so all 
                     // king radius squares are opp attack squares
-                    k.moves_as_bits_exclusive(&0u128, &0u128, &mut info.opp_attacks);
+                    k.moves_as_bits_exclusive(&0u64, &0u64, &mut info.opp_attacks);
                 }
                 Pieces::Knight(n) => {
                     let opp = (info.team_pieces & !info.king.bits()) | info.opp_pieces;
-                    n.moves_as_bits_exclusive(&opp, &0u128, &mut info.opp_attacks);
+                    n.moves_as_bits_exclusive(&opp, &0u64, &mut info.opp_attacks);
                 }
                 Pieces::Bishop(b) => {
                     let opp = (info.team_pieces & !info.king.bits()) | info.opp_pieces;
-                    b.moves_as_bits_exclusive(&opp, &0u128, &mut info.opp_attacks);
-                    info.opp_diagonal |= b.bits();
+                    b.moves_as_bits_exclusive(&opp, &0u64, &mut info.opp_attacks);
                 }
                 Pieces::Rook(r) => {
                     let opp = (info.team_pieces & !info.king.bits()) | info.opp_pieces;
-                    r.moves_as_bits_exclusive(&opp, &0u128, &mut info.opp_attacks);
-                    info.opp_straight |= r.bits();
+                    r.moves_as_bits_exclusive(&opp, &0u64, &mut info.opp_attacks);
                 }
                 Pieces::Queen(q) => {
                     let opp = (info.team_pieces & !info.king.bits()) | info.opp_pieces;
-                    q.moves_as_bits_exclusive(&opp, &0u128, &mut info.opp_attacks);
-                    info.opp_diagonal |= q.bits();
-                    info.opp_straight |= q.bits();
+                    q.moves_as_bits_exclusive(&opp, &0u64, &mut info.opp_attacks);
                 }
             }
         }
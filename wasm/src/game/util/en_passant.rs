@@ -1,19 +1,19 @@
 pub mod constants {
     // the en passant src and dst rows for corresponding colors
-    pub const WHITE_EN_PASSANT: u128 = 0xff00 << 0x10 | 0xff00 << 0x30;
-    pub const BLACK_EN_PASSANT: u128 = 0xff00 << 0x60 | 0xff00 << 0x40;
+    pub const WHITE_EN_PASSANT: u64 = 0xff << 0x08 | 0xff << 0x18;
+    pub const BLACK_EN_PASSANT: u64 = 0xff << 0x30 | 0xff << 0x20;
 }
 pub use constants::*;
 
 #[inline]
-pub fn add_en_passant (mv: &u128) -> Option<u128> {
+pub fn add_en_passant (mv: &u64) -> Option<u64> {
     if mv & WHITE_EN_PASSANT == *mv {
-        let ep = mv & (0xff00 << 0x10);
-        return Some(ep << 0x10);
+        let ep = mv & (0xff << 0x08);
+        return Some(ep << 0x08);
     }
     else if mv & BLACK_EN_PASSANT == *mv {
-        let ep = mv & (0xff00 << 0x60);
-        return Some(ep >> 0x10);
+        let ep = mv & (0xff << 0x30);
+        return Some(ep >> 0x08);
     }
     return None;
 }
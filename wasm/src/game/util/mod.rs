@@ -1,9 +1,9 @@
 use crate::game::{
     Game,
+    GameError,
     pieces::{
-        Color::White,
-        Color::Black,
-        Piece,
+        Kind,
+        King,
         Pieces,
     },
 };
@@ -14,57 +14,52 @@ pub mod en_passant;
 pub mod promote;
 pub mod check;
 
-pub struct GameInfo<'a> {
+pub struct GameInfo {
     pub valid_moves: u16,
-    pub king: &'a Pieces,
+    pub king: Pieces,
     pub check: bool,
     pub double_check: bool,
     pub checkmate: bool,
     pub stalemate: bool,
-    pub team_pieces: u128,
-    pub opp_pieces: u128,
-    pub opp_attacks: u128,
+    pub team_pieces: u64,
+    pub opp_pieces: u64,
+    pub opp_attacks: u64,
 
     /* opp directional used for pin detection */
-    pub opp_diagonal: u128, // bishop and queen
-    pub opp_straight: u128, // rook and queen
+    pub opp_diagonal: u64, // bishop and queen
+    pub opp_straight: u64, // rook and queen
+
+    pub checkers: u64, // opp pieces giving check
+    pub pinned: u64,   // team pieces pinned to the king
 }
 
-impl<'a> GameInfo<'a> {
+impl GameInfo {
     #[inline]
-    pub fn init(game: &'a Game) -> Self {
-        let mut king: Option<&'a Pieces> = None;
-        let check = false;
-        let (mut team_pieces, mut opp_pieces) = (0u128, 0u128);
-        let opp_attacks = 0u128;
-        let (opp_diagonal, opp_straight) = (0u128, 0u128);
-
-        for piece in &game.pieces {
-            match (game.turn, piece.color()) {
-                (White, White) | (Black, Black) => {
-                    if let Pieces::King(_) = piece {
-                        king = Some(piece);
-                    }
-                    team_pieces |= piece.bits()
-                },
-                (White, Black) | (Black, White) => {
-                    opp_pieces |= piece.bits()
-                },
-            }
+    pub fn init(game: &Game) -> Result<Self, GameError> {
+        let king = game.board.get(Kind::King, game.turn);
+        if king == 0 {
+            return Err(GameError::MissingKing { color: game.turn });
         }
 
-        GameInfo {
+        let them = game.turn.opponent();
+        let queens = game.board.get(Kind::Queen, them);
+
+        let mut info = GameInfo {
             valid_moves: 0,
-            king: king.unwrap(),
-            check,
+            king: Pieces::King(King::from_bits(king, game.turn)),
+            check: false,
             checkmate: false,
             double_check: false,
             stalemate: false,
-            team_pieces,
-            opp_pieces,
-            opp_attacks,
-            opp_diagonal,
-            opp_straight,
-        }
+            team_pieces: game.board.color(game.turn),
+            opp_pieces: game.board.color(game.turn.opponent()),
+            opp_attacks: 0,
+            opp_diagonal: game.board.get(Kind::Bishop, them) | queens,
+            opp_straight: game.board.get(Kind::Rook, them) | queens,
+            checkers: 0,
+            pinned: 0,
+        };
+        check::find_checks(&mut info, game)?;
+        Ok(info)
     }
 }
@@ -5,10 +5,7 @@ use crate::game::pieces::{
     Piece,
     Pieces,
     Pawn,
-    Bishop,
-    Knight,
-    Rook,
-    Queen,
+    Kind,
 };
 
 pub mod constants {
@@ -18,16 +15,16 @@ pub mod constants {
     // No indicator is a Queen promotion
 
     // White promotion option indicators
-    pub const WHITE_ROOK: u128 = 0x0100;   // x.......
-    pub const WHITE_KNIGHT: u128 = 0x0200; // .x......
-    pub const WHITE_BISHOP: u128 = 0x0400; // ..x.....
-    pub const WHITE_BACK_RANK: u128 = 0xff00 << 0x70;
+    pub const WHITE_ROOK: u64 = 0x01;   // x.......
+    pub const WHITE_KNIGHT: u64 = 0x02; // .x......
+    pub const WHITE_BISHOP: u64 = 0x04; // ..x.....
+    pub const WHITE_BACK_RANK: u64 = 0xff << 0x38;
 
     // Black promotion option indicators
-    pub const BLACK_ROOK: u128 = 0x0100 << 0x70;   // x.......
-    pub const BLACK_KNIGHT: u128 = 0x0200 << 0x70; // .x......
-    pub const BLACK_BISHOP: u128 = 0x0400 << 0x70; // ..x.....
-    pub const BLACK_BACK_RANK: u128 = 0xff00;
+    pub const BLACK_ROOK: u64 = 0x01 << 0x38;   // x.......
+    pub const BLACK_KNIGHT: u64 = 0x02 << 0x38; // .x......
+    pub const BLACK_BISHOP: u64 = 0x04 << 0x38; // ..x.....
+    pub const BLACK_BACK_RANK: u64 = 0xff;
 }
 pub use constants::*;
 
@@ -50,46 +47,22 @@ pub fn add_promotions(mv: &Pieces, moves: &mut Vec<Pieces>) {
     }
 }
 
+// strips the indicator off a pawn move reaching the
+// back rank and returns the piece the pawn becomes
 #[inline]
-pub fn try_promote (piece: &mut Pieces, mv: &mut u128, turn: Color) {
-    match turn {
-        White => {
-            if *mv & WHITE_BACK_RANK == 0 { return; }
-            let id = *mv & BLACK_BACK_RANK;
-            match id {
-                WHITE_BISHOP => {
-                    *piece = Pieces::Bishop(Bishop::from_bits(*piece.bits(), White));
-                    *mv &= !WHITE_BISHOP;
-                },
-                WHITE_KNIGHT => {
-                    *piece = Pieces::Knight(Knight::from_bits(*piece.bits(), White));
-                    *mv &= !WHITE_KNIGHT;
-                },
-                WHITE_ROOK => {
-                    *piece = Pieces::Rook(Rook::from_bits(*piece.bits(), White));
-                    *mv &= !WHITE_ROOK;
-                },
-                _ => *piece = Pieces::Queen(Queen::from_bits(*piece.bits(), White))
-            }
-        },
-        Black => {
-            if *mv & BLACK_BACK_RANK == 0 { return; }
-            let id = *mv & WHITE_BACK_RANK;
-            match id {
-                BLACK_BISHOP => {
-                    *piece = Pieces::Bishop(Bishop::from_bits(*piece.bits(), Black));
-                    *mv &= !BLACK_BISHOP;
-                },
-                BLACK_KNIGHT => {
-                    *piece = Pieces::Knight(Knight::from_bits(*piece.bits(), Black));
-                    *mv &= !BLACK_KNIGHT;
-                },
-                BLACK_ROOK => {
-                    *piece = Pieces::Rook(Rook::from_bits(*piece.bits(), Black));
-                    *mv &= !BLACK_ROOK;
-                },
-                _ => *piece = Pieces::Queen(Queen::from_bits(*piece.bits(), Black))
-            }
-        }
-    }
+pub fn try_promote(mv: &mut u64, turn: Color) -> Option<Kind> {
+    let (back_rank, indicators) = match turn {
+        White => (WHITE_BACK_RANK, BLACK_BACK_RANK),
+        Black => (BLACK_BACK_RANK, WHITE_BACK_RANK),
+    };
+    if *mv & back_rank == 0 { return None; }
+
+    let id = *mv & indicators;
+    *mv &= !id;
+    Some(match id {
+        WHITE_BISHOP | BLACK_BISHOP => Kind::Bishop,
+        WHITE_KNIGHT | BLACK_KNIGHT => Kind::Knight,
+        WHITE_ROOK | BLACK_ROOK => Kind::Rook,
+        _ => Kind::Queen,
+    })
 }
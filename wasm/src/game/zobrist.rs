@@ -0,0 +1,60 @@
+use super::{
+    Game,
+    board::{Bits, index},
+    pieces::{Color, KINDS},
+    util::castle::IDS,
+};
+
+/* Zobrist keys, one for each piece on each square, then the
+ * side to move, the four castling rights and the en passant
+ * file. Filled at compile time from a fixed xorshift stream
+ */
+const PIECES: usize = 0;
+const TURN: usize = 768;
+const CASTLING: usize = 769;
+const EN_PASSANT: usize = 773;
+
+const fn keys() -> [u64; 781] {
+    let mut keys = [0; 781];
+    let mut seed = 0x9e37_79b9_7f4a_7c15u64;
+    let mut i = 0;
+    while i < keys.len() {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        keys[i] = seed;
+        i += 1;
+    }
+    keys
+}
+
+const KEYS: [u64; 781] = keys();
+
+// hash of the whole position, equal positions hash the same
+pub fn hash(game: &Game) -> u64 {
+    let mut key = 0;
+    for (c, color) in [Color::White, Color::Black].into_iter().enumerate() {
+        for kind in KINDS {
+            let offset = PIECES + (c * 6 + kind as usize) * 64;
+            for bit in Bits(game.board.get(kind, color)) {
+                key ^= KEYS[offset + index(bit)];
+            }
+        }
+    }
+
+    if game.turn == Color::Black {
+        key ^= KEYS[TURN];
+    }
+
+    // the rook file is folded in so Chess960 rights stay distinct
+    for (i, id) in IDS.iter().enumerate() {
+        if game.castling & id != 0 {
+            key ^= KEYS[CASTLING + i].rotate_left(game.castling_rooks[i] as u32);
+        }
+    }
+
+    if game.en_passant_square != 0 {
+        key ^= KEYS[EN_PASSANT + index(game.en_passant_square) % 8];
+    }
+    key
+}
@@ -1,4 +1,5 @@
 pub mod chess;
+pub mod error;
 pub mod game;
 pub mod engine;
 mod test;
@@ -0,0 +1,113 @@
+#[cfg(test)]
+mod test {
+use crate::{
+    game::{fen, notation::algebraic_to_bits, pieces::{Color::*, *}},
+    test::test::{xorshift, KIWIPETE},
+};
+
+#[test]
+fn test_board() {
+    use crate::game::{board::*, notation::bits_to_algebraic};
+
+    assert_eq!(algebraic_to_bits("a1".into()).unwrap(), 1);
+    assert_eq!(algebraic_to_bits("h8".into()).unwrap(), 1 << 63);
+    for i in 0..64 {
+        let bits = 1u64 << i;
+        assert_eq!(algebraic_to_bits(bits_to_algebraic(&bits).unwrap()).unwrap(), bits);
+    }
+
+    // steps never wrap around the board's edges
+    assert_eq!(east(square(7, 3)), 0);
+    assert_eq!(west(square(0, 3)), 0);
+    assert_eq!(north_east(square(7, 0)), 0);
+    assert_eq!(south_west(square(0, 7)), 0);
+
+    let game = fen::decode("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+    assert_eq!(game.board.get(Kind::Pawn, White), RANK_2);
+    assert_eq!(game.board.get(Kind::King, Black), square(4, 7));
+    assert_eq!(game.board.occupied(), RANK_1 | RANK_2 | RANK_7 | RANK_8);
+    assert_eq!(game.board.kind_at(square(3, 0)), Some((Kind::Queen, White)));
+    assert_eq!(game.pieces().count(), 32);
+    assert_eq!(Board::from_pieces(&game.pieces().collect::<Vec<_>>()), game.board);
+}
+
+#[test]
+fn test_attacks() {
+    use crate::game::{attacks, board::*};
+
+    // sparse pseudo-random blockers, xorshift
+    let mut random = xorshift(0x2545_f491_4f6c_dd1d);
+    for square in 0..64 {
+        for _ in 0..200 {
+            let occupied = random() & random();
+            assert_eq!(attacks::rook(square, occupied), attacks::rook_rays(square, occupied));
+            assert_eq!(attacks::bishop(square, occupied), attacks::bishop_rays(square, occupied));
+        }
+    }
+
+    assert_eq!(attacks::knight(0), square(1, 2) | square(2, 1));
+    assert_eq!(attacks::king(63), square(6, 7) | square(6, 6) | square(7, 6));
+    assert_eq!(attacks::pawn(White, 8), square(1, 2));
+    assert_eq!(attacks::pawn(Black, 55), square(6, 5));
+    assert_eq!(attacks::queen(0, 0).count_ones(), 21);
+}
+
+#[test]
+fn test_mailbox() {
+    use crate::game::{Game, board::*};
+
+    fn walk(game: &Game, depth: u32) {
+        for square in 0..64 {
+            let bits = 1u64 << square;
+            let expected = game.pieces().find(|p| p.bits() & bits != 0);
+            let found = game.piece_at(square);
+            assert_eq!(found.as_ref().map(|p| (p.kind(), *p.color())), expected.map(|p| (p.kind(), *p.color())));
+        }
+        assert_eq!(Bits(game.board.occupied()).count(), game.pieces().count());
+        if depth == 0 { return; }
+        for m in game.moves().unwrap() {
+            let mut node = game.clone();
+            node.move_piece(*m.bits());
+            walk(&node, depth - 1);
+        }
+    }
+
+    // castling both ways, en passant, promotions and captures
+    walk(&fen::decode(KIWIPETE).unwrap(), 2);
+    walk(&fen::decode("r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1").unwrap(), 2);
+    walk(&fen::decode("1r4kr/8/8/8/8/8/8/1R4KR w BHbh - 0 1").unwrap(), 2);
+    // the king and rook swap squares
+    walk(&fen::decode("qnnrbkrb/pppppppp/8/8/8/8/PPPPPPPP/QNNRBKRB w KQkq - 0 1").unwrap(), 1);
+
+    let game = fen::decode("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+    assert!(matches!(game.piece_at(4), Some(Pieces::King(_))));
+    assert!(game.piece_at(28).is_none());
+}
+
+#[test]
+fn test_pins() {
+    use crate::game::{attacks, board::*};
+
+    assert_eq!(attacks::between(0, 7), 0x7e);
+    assert_eq!(attacks::between(0, 63), 0x0040_2010_0804_0200);
+    assert_eq!(attacks::between(0, 17), 0);
+    assert_eq!(attacks::line(9, 18), 0x8040_2010_0804_0201);
+    assert_eq!(attacks::line(8, 9), RANK_2);
+
+    // bishop b4 pins the d2 knight, rook e8 checks through e-file
+    let game = fen::decode("4r1k1/8/8/8/1b6/8/3N4/4K3 w - - 0 1").unwrap();
+    assert_eq!(game.checkers().unwrap(), square(4, 7));
+    assert_eq!(game.pinned().unwrap(), square(3, 1));
+    assert_eq!(game.moves().unwrap().len(), 3); // Nd2-e4 cannot block
+
+    // en passant would leave the king on an open rank
+    let game = fen::decode("8/8/8/KPp4r/8/8/8/7k w - c6 0 1").unwrap();
+    assert_eq!(game.pinned().unwrap(), 0);
+    assert!(game.moves().unwrap().iter().all(|m| m.bits() & square(2, 5) == 0));
+
+    // the double pushed pawn gives check and is taken en passant
+    let game = fen::decode("8/8/8/2k5/3Pp3/8/8/4K3 b - d3 0 1").unwrap();
+    assert_eq!(game.checkers().unwrap(), square(3, 3));
+    assert!(game.moves().unwrap().iter().any(|m| *m.bits() == square(4, 3) | square(3, 2)));
+}
+}
@@ -0,0 +1,70 @@
+#[cfg(test)]
+mod test {
+use crate::game::{
+    fen,
+    perft::perft,
+    pieces::Piece,
+    notation::{algebraic_to_bits, to_san},
+};
+
+/* Positions from https://www.chessprogramming.org/Chess960_Perft_Results
+ * and a few castling edge cases, counts agree with shakmaty
+ */
+#[test]
+fn test_chess960_perft() {
+    let suite = [
+        ("bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9", [21, 528, 12_189]),
+        ("2nnrbkr/p1qppppp/8/1ppb4/6PP/3PP3/PPP2P2/BQNNRBKR w HEhe - 1 9", [21, 807, 18_002]),
+        ("b1q1rrkb/pppppppp/3nn3/8/P7/1PPP4/4PPPP/BQNNRKRB w GE - 1 9", [20, 479, 10_471]),
+        ("qbbnnrkr/2pp2pp/p7/1p2pp2/8/P3PP2/1PPP1KPP/QBBNNR1R w hf - 0 9", [22, 593, 13_440]),
+        ("1nbbnrkr/p1p1ppp1/3p4/1p3P1p/3Pq2P/8/PPP1P1P1/QNBBNRKR w HFhf - 0 9", [28, 1_120, 31_058]),
+        ("qnbnr1kr/ppp1b1pp/4p3/3p1p2/8/2NPP3/PPP1BPPP/QNB1R1KR w HEhe - 1 9", [29, 899, 26_578]),
+        ("rk2r3/8/8/8/8/8/8/RK2R3 w AEae - 0 1", [23, 442, 9_899]),
+        ("1r2k1r1/8/8/8/8/8/8/R3K1R1 w AGbg - 0 1", [25, 560, 13_369]),
+        ("1r4kr/8/8/8/8/8/8/1R4KR w BHbh - 0 1", [24, 479, 10_954]),
+        ("4k3/8/8/8/8/8/8/r1RK4 w C - 0 1", [6, 74, 1_052]),
+        ("4k3/8/8/8/8/8/8/2RK3r w C - 0 1", [3, 51, 769]),
+    ];
+    for (fen, counts) in suite {
+        let game = fen::decode(fen).unwrap();
+        for (depth, count) in counts.into_iter().enumerate() {
+            assert_eq!(perft(&game, depth as u32 + 1, None).unwrap(), count, "{} depth {}", fen, depth + 1);
+        }
+    }
+}
+
+#[test]
+fn test_chess960_positions() {
+    assert_eq!(fen::chess960(518).unwrap(), "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+    assert_eq!(fen::chess960(0).unwrap(), "bbqnnrkr/pppppppp/8/8/8/8/PPPPPPPP/BBQNNRKR w KQkq - 0 1");
+    assert_eq!(fen::chess960(959).unwrap(), "rkrnnqbb/pppppppp/8/8/8/8/PPPPPPPP/RKRNNQBB w KQkq - 0 1");
+    assert!(fen::chess960(960).is_err());
+
+    // some start positions can castle at once, others have
+    // knights boxed in, the total agrees with shakmaty
+    let mut total = 0;
+    for i in 0..960 {
+        let game = fen::decode(&fen::chess960(i).unwrap()).unwrap();
+        total += game.moves().unwrap().len();
+        assert!(fen::validate_strict(&fen::encode(&game).unwrap()).is_ok());
+    }
+    assert_eq!(total, 18_882);
+}
+
+#[test]
+fn test_king_takes_rook() {
+    // king on g1 castles king side without moving
+    let mut game = fen::decode("1r4kr/8/8/8/8/8/8/1R4KR w BHbh - 0 1").unwrap();
+    assert!(game.chess960);
+    let mv = algebraic_to_bits("g1".into()).unwrap() | algebraic_to_bits("h1".into()).unwrap();
+    game.valid_move(&mv).unwrap();
+    assert_eq!(to_san(&game, &game.moves().unwrap().into_iter().find(|m| *m.bits() == mv).unwrap()).unwrap(), "O-O");
+    game.move_piece(mv);
+    assert_eq!(fen::encode(&game).unwrap(), "1r4kr/8/8/8/8/8/8/1R3RK1 b kq - 1 1");
+
+    // queen side, king g8 takes its own rook on b8, ending on c8 with the rook on d8
+    let mv = algebraic_to_bits("g8".into()).unwrap() | algebraic_to_bits("b8".into()).unwrap();
+    game.move_piece(mv);
+    assert_eq!(fen::encode(&game).unwrap(), "2kr3r/8/8/8/8/8/8/1R3RK1 w - - 2 2");
+}
+}
@@ -0,0 +1,44 @@
+#[cfg(test)]
+mod test {
+use crate::game::fen;
+
+#[test]
+fn test_engine_config() {
+    use crate::engine::{evaluate, from_centipawns, EngineConfig};
+    use crate::error::ErrorCode;
+
+    // fields left out keep their defaults
+    let config = EngineConfig::from_json(r#"{ "contempt": 50, "aggressiveness": 25 }"#).unwrap();
+    assert_eq!(config, EngineConfig { contempt: 50, aggressiveness: 25, ..Default::default() });
+    assert_eq!(EngineConfig::from_json(&config.to_json()).unwrap(), config);
+    assert_eq!(EngineConfig::from_json(r#"{ "contempts": 50 }"#).unwrap_err().code(), "ENGINE_CONFIG");
+    assert_eq!(EngineConfig::from_json("{}").unwrap(), EngineConfig::default());
+
+    // the queen and knight bearing on the black king
+    let game = fen::decode("6k1/5ppp/8/3Q2N1/8/8/8/6K1 w - - 0 1").unwrap();
+    let info = game.info().unwrap();
+    let plain = evaluate(&game, &info, &EngineConfig::default());
+    let aggressive = EngineConfig { aggressiveness: 50, ..Default::default() };
+    assert_eq!(evaluate(&game, &info, &aggressive), plain + 2);
+
+    // centipawns are rounded once, both attackers together make a half pawn
+    let aggressive = EngineConfig { aggressiveness: 25, ..Default::default() };
+    assert_eq!(evaluate(&game, &info, &aggressive), plain + 1);
+    assert_eq!([25, -25, 24, -24, 100].map(from_centipawns), [1, -1, 0, 0, 2]);
+
+    // material counts for nothing without materialism
+    let indifferent = EngineConfig { materialism: 0, ..Default::default() };
+    assert!(evaluate(&game, &info, &indifferent) < plain);
+
+    // being ahead earns a flat bonus until the endgame, simplification adds to it
+    let score = |fen: &str, config: &EngineConfig| {
+        let game = fen::decode(fen).unwrap();
+        evaluate(&game, &game.info().unwrap(), config)
+    };
+    let (middlegame, endgame) = ("4k3/8/8/8/8/8/8/R3K3 w - - 0 10", "4k3/8/8/8/8/8/8/R3K3 w - - 0 40");
+    let default = EngineConfig::default();
+    assert_eq!(score(middlegame, &default), score(endgame, &default) + 2);
+    let trading = EngineConfig { simplification: 100, ..Default::default() };
+    assert!(score(endgame, &trading) > score(endgame, &default));
+}
+}
@@ -0,0 +1,62 @@
+#[cfg(test)]
+mod test {
+use crate::{
+    engine::{run_suite, SearchLimits},
+    game::{epd::{self, EpdError}, fen, notation::{to_san, from_san}},
+    test::test::KIWIPETE,
+};
+
+#[test]
+fn test_epd_decode() {
+    let e = epd::decode(r#"r1b1k2r/ppppnppp/2n2q2/2b5/3NP3/2P1B3/PP3PPP/RN1QKB1R w KQkq - bm Nb5; id "ECM.001"; c0 "a; b"; acd 4; ce -35;"#).unwrap();
+    assert_eq!(e.fen, "r1b1k2r/ppppnppp/2n2q2/2b5/3NP3/2P1B3/PP3PPP/RN1QKB1R w KQkq - 0 1");
+    assert_eq!(e.best_moves, vec!["Nb5"]);
+    assert_eq!(e.id.as_deref(), Some("ECM.001"));
+    assert_eq!(e.comment.as_deref(), Some("a; b"));
+    assert_eq!(e.depth, Some(4));
+    assert_eq!(e.eval, Some(-35));
+    assert_eq!(e.operations.len(), 5);
+
+    let e = epd::decode("8/8/8/8/8/8/8/K1k5 b - - hmvc 12; fmvn 40").unwrap();
+    assert_eq!(fen::encode(&e.game).unwrap(), "8/8/8/8/8/8/8/K1k5 b - - 12 40");
+
+    // any run of whitespace separates the fields
+    let e = epd::decode("8/8/8/8/8/8/8/K1k5  b\t-   -  acd 3;  c0 \"two  spaces\";").unwrap();
+    assert_eq!((e.depth, e.comment.as_deref()), (Some(3), Some("two  spaces")));
+
+    // operands out of their range are errors, not wrapped
+    for bad in ["acd -1", "acd 4294967296", "ce 2147483648", "hmvc -3"] {
+        let e = epd::decode(&format!("8/8/8/8/8/8/8/K1k5 b - - {};", bad));
+        assert!(matches!(e, Err(EpdError::BadOperand { .. })), "{}", bad);
+    }
+
+    assert!(epd::decode("8/8/8/8/8/8/8/K1k5 b -").is_err());
+    assert!(epd::decode(r#"8/8/8/8/8/8/8/K1k5 b - - id "open"#).is_err());
+}
+
+#[test]
+fn test_san() {
+    let game = fen::decode(KIWIPETE).unwrap();
+    for mv in game.moves().unwrap() {
+        let san = to_san(&game, &mv).unwrap();
+        let found = from_san(&game, &san).unwrap();
+        assert_eq!(to_san(&game, &found).unwrap(), san);
+    }
+    assert!(from_san(&game, "O-O").is_ok());
+    assert!(from_san(&game, "Qxf6").is_ok());
+    assert!(from_san(&game, "Nxd7").is_ok());
+    assert!(from_san(&game, "Qh8").is_err());
+}
+
+#[test]
+fn test_suite() {
+    let suite = vec![
+        epd::decode(r#"6k1/5ppp/8/8/8/8/8/R5K1 w - - bm Ra8#; id "back rank";"#).unwrap(),
+        epd::decode(r#"6k1/5ppp/8/8/8/8/8/R5K1 w - - am Ra8; id "avoid";"#).unwrap(),
+    ];
+    let report = run_suite(&suite, &SearchLimits { depth: Some(2), threads: Some(1), ..Default::default() });
+    assert_eq!(report.solved, 1);
+    assert_eq!(report.failed, 1);
+    assert_eq!(report.results[0].played.as_deref(), Some("Ra8#"));
+}
+}
@@ -0,0 +1,25 @@
+#[cfg(test)]
+mod test {
+use crate::game::{fen, notation::algebraic_to_bits};
+
+#[test]
+fn test_errors() {
+    use crate::{error::ErrorCode, game::{GameError, MoveError, fen::FenError, notation::NotationError}};
+
+    let err = fen::decode("8/8/8/8/8/8/8/KK5k w - - 0 1").err().unwrap();
+    assert_eq!(err, FenError::TooManyKings);
+    assert_eq!(err.code(), "FEN_TOO_MANY_KINGS");
+    assert_eq!(fen::decode("8/8/8/8/8/8/8/K6k w - e3 0 1").err(), Some(FenError::BadEnPassant { square: "e3".into() }));
+    assert_eq!(fen::decode("8/8/8/8/8/8/8/K6k w - - 0 x").err(), Some(FenError::BadMoveNumber { number: "x".into() }));
+    assert_eq!(algebraic_to_bits("i9".into()).err(), Some(NotationError::BadSquare { square: "i9".into() }));
+    assert!(algebraic_to_bits("".into()).is_err());
+
+    // adjacent kings are an error, not a panic
+    let game = fen::decode("8/8/8/8/8/8/8/Kk6 w - - 0 1").unwrap();
+    assert_eq!(game.moves().err(), Some(GameError::KingGivesCheck));
+
+    let game = fen::decode("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+    let mv = algebraic_to_bits("e2".into()).unwrap() | algebraic_to_bits("e5".into()).unwrap();
+    assert_eq!(game.valid_move(&mv), Err(MoveError::Illegal { mv: "e2e5".into() }));
+}
+}
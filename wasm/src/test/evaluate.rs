@@ -0,0 +1,74 @@
+#[cfg(test)]
+mod test {
+use crate::{game::fen, test::test::KIWIPETE};
+
+#[test]
+fn test_evaluate_traced() {
+    use crate::engine::{
+        evaluate, evaluate_traced, EngineConfig, Trace, MATE, WEIGHTS,
+        evaluate::{evaluate_traced_in, evaluate_traced_with, GameState, Term, Weights},
+    };
+
+    // the terms add up to the evaluation from either side
+    let config = EngineConfig { aggressiveness: 50, materialism: 150, ..Default::default() };
+    for fen in [
+        "r1bqkb1r/pppp1ppp/2n2n2/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 4 4",
+        "6k1/5ppp/8/3Q2N1/8/8/8/6K1 b - - 30 40",
+        "4k3/8/8/8/8/8/3q4/3RK3 w - - 0 1",
+    ] {
+        let game = fen::decode(fen).unwrap();
+        let info = game.info().unwrap();
+        let trace = evaluate_traced(&game, &info, &config);
+        assert_eq!(trace.score(), evaluate(&game, &info, &config), "{}", fen);
+    }
+
+    // white is a queen and knight up, black is the one being attacked
+    let game = fen::decode("6k1/5ppp/8/3Q2N1/8/8/8/6K1 b - - 30 40").unwrap();
+    let trace = evaluate_traced(&game, &game.info().unwrap(), &config);
+    assert!(trace.material.white > trace.material.black);
+    assert!(trace.king_attack.white > trace.king_attack.black);
+    assert_eq!(trace.castled_king, Term { white: 2, black: 2 });
+    assert_eq!((trace.ahead, trace.fifty_moves), (Term::default(), Term::default()));
+    assert_eq!(trace.state.name(), "endgame");
+
+    // the pawn terms are untuned and play no part until they are
+    let game = fen::decode(KIWIPETE).unwrap();
+    assert_eq!(evaluate_traced(&game, &game.info().unwrap(), &config).pawn_structure, Term::default());
+
+    // pawn terms are quarter pawns, doubled and isolated against two passed
+    let weights = Weights { doubled: 1, isolated: 1, passed: 2, ..WEIGHTS };
+    let pawns = |fen: &str| {
+        let game = fen::decode(fen).unwrap();
+        evaluate_traced_with(&game, &game.info().unwrap(), &config, &weights).pawn_structure
+    };
+    assert_eq!(pawns("4k3/1p6/8/8/8/P7/P7/4K3 w - - 0 1"), Term { white: -3, black: -1 });
+    assert_eq!(pawns("4k3/8/8/8/8/8/3PP3/4K3 w - - 0 1"), Term { white: 4, black: 0 });
+
+    // a lone passed and isolated pawn is a quarter pawn, rounded to a half the same for either side
+    for fen in ["4k3/8/8/8/8/8/P7/4K3 w - - 0 20", "4k3/p7/8/8/8/8/8/4K3 b - - 0 20"] {
+        let game = fen::decode(fen).unwrap();
+        let info = game.info().unwrap();
+        let trace = evaluate_traced_with(&game, &info, &config, &weights);
+        assert_eq!(trace.pawn_structure.score(game.turn), 1);
+        assert_eq!(trace.centipawns()[2].score(game.turn), 25);
+        assert_eq!(trace.score(), evaluate_traced_with(&game, &info, &config, &WEIGHTS).score() + 1, "{}", fen);
+    }
+
+    // each phase can be asked for, the flat bonus for being ahead ends with the middlegame
+    let game = fen::decode("4k3/8/8/8/8/8/8/R3K3 w - - 0 10").unwrap();
+    let info = game.info().unwrap();
+    let phase = |state| evaluate_traced_in(&game, &info, &config, &WEIGHTS, state);
+    assert_eq!(phase(GameState::Mid).ahead.white, 2);
+    assert_eq!(phase(GameState::Late).ahead.white, 0);
+
+    // every kind of knowledge the breakdown is asked about has its terms
+    for group in ["material", "piece_square", "pawn_structure", "king_safety", "mobility", "endgame"] {
+        assert!(Trace::GROUPS.contains(&group), "{}", group);
+    }
+
+    let mated = fen::decode("R5k1/5ppp/8/8/8/8/8/6K1 b - - 0 1").unwrap();
+    let trace = evaluate_traced(&mated, &mated.info().unwrap(), &EngineConfig::default());
+    assert!(trace.checkmate);
+    assert_eq!(trace.score(), -MATE);
+}
+}
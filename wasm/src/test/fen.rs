@@ -0,0 +1,63 @@
+#[cfg(test)]
+mod test {
+use crate::game::{fen, pieces::Color::*};
+
+#[test]
+fn test_validate_strict() {
+    use crate::game::fen::FenError;
+
+    assert!(fen::validate_strict("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").is_ok());
+    assert!(fen::validate_strict("rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq e6 0 2").is_ok());
+
+    let errors = |fen: &str| fen::validate_strict(fen).err().unwrap_or_default();
+    assert_eq!(errors("4k3/8/8/8/8/8/8/P3K3 w - - 0 1"), vec![FenError::PawnOnBackRank { square: "a1".into() }]);
+    assert_eq!(errors("4k3/8/8/8/8/8/8/4K2R w Qk - 0 1"), vec![
+        FenError::BadCastlingRights { right: 'Q' },
+        FenError::BadCastlingRights { right: 'k' },
+    ]);
+    assert_eq!(errors("4k3/8/8/8/8/8/8/4K3 w - e6 0 1"), vec![FenError::EnPassantNoPawn { square: "e6".into() }]);
+    assert_eq!(errors("4k3/8/8/8/8/8/8/4K2R b - - 0 1"), vec![]);
+    assert_eq!(errors("4k3/8/8/8/8/8/8/4R1K1 w - - 0 1"), vec![FenError::OpponentInCheck]);
+    assert_eq!(errors("4k3/8/8/8/8/8/PPPPPPPP/QQ2K3 w - - 0 1"), vec![
+        FenError::TooManyPromotions { color: White },
+    ]);
+    assert_eq!(errors("4k3/8/8/8/P7/PPPPPPPP/8/4K3 w - - 0 1"), vec![FenError::TooManyPawns { color: White }]);
+
+    // lenient validation still accepts all of them
+    assert!(fen::validate("4k3/8/8/8/8/8/8/P3K3 w - - 0 1").is_ok());
+}
+
+#[test]
+fn test_castling_notation() {
+    use crate::game::fen::{encode_with, CastlingNotation::*};
+
+    let game = fen::decode("r3k2r/8/8/8/8/8/8/R3K2R w HAha - 0 1").unwrap();
+    assert_eq!(game.castling_rooks, [7, 0, 7, 0]);
+    assert_eq!(fen::encode(&game).unwrap(), "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1");
+    assert_eq!(encode_with(&game, Shredder).unwrap(), "r3k2r/8/8/8/8/8/8/R3K2R w HAha - 0 1");
+
+    // an inner rook needs its file named in X-FEN
+    let game = fen::decode("rr2k2r/8/8/8/8/8/8/1R2K1RR w GBkb - 0 1").unwrap();
+    assert_eq!(game.castling_rooks, [6, 1, 7, 1]);
+    assert_eq!(fen::encode(&game).unwrap(), "rr2k2r/8/8/8/8/8/8/1R2K1RR w GQkb - 0 1");
+    assert_eq!(encode_with(&game, Shredder).unwrap(), "rr2k2r/8/8/8/8/8/8/1R2K1RR w GBhb - 0 1");
+
+    // KQkq pick the outermost rook on each side of the king
+    let game = fen::decode("1r2k1r1/8/8/8/8/8/8/2R1KR2 w KQkq - 0 1").unwrap();
+    assert_eq!(game.castling_rooks, [5, 2, 6, 1]);
+
+    // rights left over after the king castled or walked off are not Chess960
+    for fen in [
+        "r4rk1/8/8/8/8/8/8/R4RK1 w KQkq - 0 1",
+        "r3k2r/8/8/8/8/8/8/5K2 w KQkq - 0 1",
+        "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1",
+    ] {
+        assert!(!fen::decode(fen).unwrap().chess960, "{}", fen);
+    }
+    // a shuffled king and rooks that can still castle are, as are file letters
+    assert!(fen::decode("1r4kr/8/8/8/8/8/8/1R4KR w KQkq - 0 1").unwrap().chess960);
+    assert!(fen::decode("r3k2r/8/8/8/8/8/8/R3K2R w HAha - 0 1").unwrap().chess960);
+
+    assert!(fen::decode("r3k2r/8/8/8/8/8/8/R3K2R w KQkx - 0 1").is_err());
+}
+}
@@ -0,0 +1,111 @@
+/* Plays random games checked ply by ply against the chess crate,
+ * 'https://crates.io/crates/chess'. A failing game is shrunk by
+ * proptest to the fewest and lowest move choices that still fail
+ * and reported with the FEN of the position that differs
+ *
+ * FUZZ_CASES=10000 cargo test --profile perft fuzz
+ */
+
+#[cfg(test)]
+pub mod test {
+use std::{collections::BTreeSet, str::FromStr};
+use chess::{Board, BoardStatus, MoveGen};
+use proptest::prelude::*;
+use crate::game::{
+    Game,
+    fen,
+    notation::to_uci,
+    pieces::Piece,
+};
+
+const STARTS: [&str; 5] = [
+    "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+    "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+];
+
+/* Compares a position with the reference board, the error
+ * names the first difference. Only placement, turn and
+ * castling of the FEN are compared, the chess crate drops
+ * en passant squares no pawn can take on and the clocks
+ */
+pub fn compare(game: &Game, board: &Board) -> Result<(), String> {
+    let fen = fen::encode(game).map_err(|e| e.to_string())?;
+    let again = fen::decode(&fen).and_then(|g| fen::encode(&g)).map_err(|e| e.to_string())?;
+    if again != fen {
+        return Err(format!("fen round trip gave {}", again));
+    }
+
+    let fields = |f: &str| f.split(' ').take(3).collect::<Vec<_>>().join(" ");
+    let expected = board.to_string();
+    if fields(&fen) != fields(&expected) {
+        return Err(format!("position differs from {}", expected));
+    }
+
+    let (moves, info) = game.moves_verbose().map_err(|e| e.to_string())?;
+    let ours = moves.iter().map(|m| to_uci(game, m)).collect::<Result<BTreeSet<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    let theirs = MoveGen::new_legal(board).map(|m| m.to_string()).collect::<BTreeSet<_>>();
+    if ours != theirs {
+        let list = |a: &BTreeSet<String>, b: &BTreeSet<String>| a.difference(b).cloned().collect::<Vec<_>>().join(" ");
+        return Err(format!("moves differ, missing [{}] extra [{}]", list(&theirs, &ours), list(&ours, &theirs)));
+    }
+    if ours.len() != moves.len() {
+        return Err(String::from("duplicate moves generated"));
+    }
+
+    if info.checkers.count_ones() != board.checkers().popcnt() {
+        return Err(format!("{} checkers, expected {}", info.checkers.count_ones(), board.checkers().popcnt()));
+    }
+    let status = match board.status() {
+        BoardStatus::Ongoing => (false, false),
+        BoardStatus::Checkmate => (true, false),
+        BoardStatus::Stalemate => (false, true),
+    };
+    if (info.checkmate, info.stalemate) != status {
+        return Err(format!("checkmate {} stalemate {}, expected {:?}", info.checkmate, info.stalemate, board.status()));
+    }
+    Ok(())
+}
+
+// plays the game picking each move by index into the move list
+fn play(start: &str, choices: &[usize]) -> Result<(), String> {
+    let mut game = fen::decode(start).map_err(|e| e.to_string())?;
+    let mut board = Board::from_str(start).map_err(|e| e.to_string())?;
+
+    for choice in choices {
+        let fen = fen::encode(&game).unwrap_or_default();
+        compare(&game, &board).map_err(|e| format!("{}\nfen: {}", e, fen))?;
+
+        let moves = game.moves().map_err(|e| e.to_string())?;
+        if moves.is_empty() { break; }
+        let mv = &moves[choice % moves.len()];
+
+        // the move lists agree so the reference has this move too
+        let uci = to_uci(&game, mv).map_err(|e| e.to_string())?;
+        let reference = MoveGen::new_legal(&board).find(|m| m.to_string() == uci)
+            .ok_or_else(|| format!("no reference move {}\nfen: {}", uci, fen))?;
+
+        board = board.make_move_new(reference);
+        game.move_piece(*mv.bits());
+    }
+    Ok(())
+}
+
+fn cases() -> u32 {
+    std::env::var("FUZZ_CASES").ok().and_then(|c| c.parse().ok()).unwrap_or(16)
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig { cases: cases(), ..ProptestConfig::default() })]
+
+    #[test]
+    fn test_fuzz_movegen(start in 0..STARTS.len(), choices in prop::collection::vec(any::<usize>(), 0..160)) {
+        if let Err(e) = play(STARTS[start], &choices) {
+            prop_assert!(false, "{}", e);
+        }
+    }
+}
+}
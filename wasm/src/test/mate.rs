@@ -0,0 +1,47 @@
+#[cfg(test)]
+mod test {
+use crate::{engine::Engine, game::{fen, pieces::Piece}};
+
+#[test]
+fn test_find_mate() {
+    use crate::engine::MateLimits;
+    use crate::game::notation::to_uci;
+
+    let limits = |moves, checks_only| MateLimits { moves, checks_only, ..Default::default() };
+    for (fen, moves, checks_only, mate) in [
+        ("k7/8/1K6/8/8/8/8/7R w - - 0 1", 3, false, Some(1)),
+        ("7k/8/5K2/8/8/8/8/1R6 w - - 0 1", 3, false, Some(2)),
+        ("r2qkb1r/pp2nppp/3p4/2pNN1B1/2BnP3/3P4/PPP2PPP/R2bK2R w KQkq - 1 1", 2, true, Some(2)),
+        // the only mate starts with a quiet king move
+        ("7k/8/5K2/8/8/8/8/1R6 w - - 0 1", 2, true, None),
+        ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", 2, false, None),
+    ] {
+        let game = fen::decode(fen).unwrap();
+        let tree = Engine::find_mate(&game, &limits(moves, checks_only)).unwrap();
+        assert_eq!(tree.as_ref().map(|t| t.moves), mate, "{}", fen);
+
+        // every defence runs into mate on the attacker's last move
+        let Some(tree) = tree else { continue };
+        let mut nodes = vec![(game, tree)];
+        while let Some((game, tree)) = nodes.pop() {
+            let mut next = game.clone();
+            next.move_piece(*tree.mv.bits());
+            let replies = next.moves().unwrap();
+            assert_eq!(replies.len(), tree.defences.len(), "{}", to_uci(&game, &tree.mv).unwrap());
+            if replies.is_empty() { assert!(next.info().unwrap().checkmate); }
+            for (reply, t) in tree.defences {
+                let mut after = next.clone();
+                after.move_piece(*reply.bits());
+                assert!(t.moves < tree.moves);
+                nodes.push((after, t));
+            }
+        }
+    }
+
+    // a stopped search gives up instead of running on
+    let stop = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(true));
+    let game = fen::decode("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+    let limits = MateLimits { moves: 10, stop: Some(stop), ..Default::default() };
+    assert!(matches!(Engine::find_mate(&game, &limits), Err(crate::engine::EngineError::OutOfTime)));
+}
+}
@@ -1,6 +1,21 @@
 mod perft;
+mod epd;
+mod chess960;
+mod fuzz;
+mod errors;
+mod fen;
+mod board;
+mod notation;
+mod search;
+mod mate;
+mod config;
+mod time;
+mod ponder;
+mod evaluate;
+mod tune;
+mod nnue;
 #[cfg(test)]
-mod test {
+pub mod test {
 #[allow(unused)]
 use crate::{game::notation::algebraic_to_bits, engine::Engine};
 
@@ -9,6 +24,22 @@ use super::super::game::{
     pieces::{Color::*, *},
 };
 
+// positions shared by the tests of several modules
+pub const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+pub const BACK_RANK: &str = "6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1";
+// Ra8#, the mate in one of the back rank position
+pub const BACK_RANK_MATE: u64 = 0x01 | 0x01 << 0x38;
+
+// a xorshift generator, the same numbers on every run
+pub fn xorshift(mut seed: u64) -> impl FnMut() -> u64 {
+    move || {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        seed
+    }
+}
+
 /* cargo test [TEST NAME] -- --nocapture */
 #[test]
 fn test_fen() {
@@ -24,7 +55,7 @@ fn test_move() {
     //std::env::set_var("RUST_BACKTRACE", "1");
     let game = fen::decode("1n2k2r/r7/p6p/5Np1/Pp2n1P1/8/1PP2QKP/R7 b k - 0 31").unwrap();
     let (mut w, mut b) = (0, 0);
-    for p in &game.pieces {
+    for p in game.pieces() {
         match p.color() {
             White => w |= p.bits(),
             Black => b |= p.bits(),
@@ -36,10 +67,10 @@ fn test_move() {
     print_bits(mv.bits(), 'x');
 }
 
-pub fn print_bits(x: &u128, c: char) {
-    for i in (-15..=112).rev().step_by(16) { // 0..128 but with rev-step
+pub fn print_bits(x: &u64, c: char) {
+    for i in (0..64).rev().step_by(8) {
         for j in 0..8 {
-            print!("{} ", (x >> (i + j + 8) & 1)
+            print!("{} ", (x >> (i - 7 + j) & 1)
                    .to_string()
                    .replace('1', &c.to_string())
                    .replace('0', "."));
@@ -0,0 +1,78 @@
+#[cfg(test)]
+mod test {
+use crate::{
+    engine::Engine,
+    game::{fen, pieces::Piece},
+    test::test::{xorshift, BACK_RANK, BACK_RANK_MATE, KIWIPETE},
+};
+
+#[test]
+fn test_nnue() {
+    use std::sync::Arc;
+    use crate::engine::{Network, SearchLimits};
+    use crate::error::ErrorCode;
+
+    // a random network, 16 hidden
+    let hidden = 16;
+    let mut next = xorshift(0x2545_f491_4f6c_dd1d);
+    let mut random = |range: i64| ((next() % (2 * range as u64 + 1)) as i64 - range) as i16;
+    let mut bytes = b"CWNN".to_vec();
+    bytes.extend_from_slice(&1u32.to_le_bytes());
+    bytes.extend_from_slice(&(hidden as u32).to_le_bytes());
+    for _ in 0..(768 + 3) * hidden {
+        bytes.extend_from_slice(&random(64).to_le_bytes());
+    }
+    bytes.extend_from_slice(&100i32.to_le_bytes());
+
+    let network = Network::from_bytes(&bytes).unwrap();
+    assert_eq!(network.hidden(), hidden);
+    assert_eq!(network.to_bytes(), bytes);
+    assert_eq!(Network::from_bytes(&bytes[..bytes.len() - 1]).unwrap_err().code(), "ENGINE_NETWORK");
+    assert!(Network::from_bytes(&[bytes.as_slice(), &[0]].concat()).is_err());
+    assert!(Network::from_bytes(b"CWNX").is_err());
+
+    // updated along a game the accumulator matches one built from scratch,
+    // through castling, en passant and promotions
+    for start in [
+        KIWIPETE,
+        "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    ] {
+        let mut game = fen::decode(start).unwrap();
+        let mut accumulator = network.refresh(&game);
+        for _ in 0..80 {
+            let moves = game.moves().unwrap();
+            if moves.is_empty() { break; }
+            let mut next = game.clone();
+            next.move_piece(*moves[random(1000).unsigned_abs() as usize % moves.len()].bits());
+            accumulator = network.update(&accumulator, &game, &next);
+            assert_eq!(accumulator, network.refresh(&next), "{}", fen::encode(&next).unwrap());
+            game = next;
+        }
+    }
+
+    // the same position with the colors swapped scores the same
+    let game = fen::decode("r1bqkb1r/pppp1ppp/2n2n2/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 4 4").unwrap();
+    let mirror = fen::decode("rnb1k1nr/pppp1ppp/8/2b1p3/4P2q/2N2N2/PPPP1PPP/R1BQKB1R b KQkq - 4 4").unwrap();
+    assert_eq!(network.evaluate(&network.refresh(&game), game.turn), network.evaluate(&network.refresh(&mirror), mirror.turn));
+
+    // every neuron fully on against the largest weights sums past an i32, the score stays bounded
+    let wide = 1024;
+    let mut bytes = b"CWNN".to_vec();
+    bytes.extend_from_slice(&1u32.to_le_bytes());
+    bytes.extend_from_slice(&(wide as u32).to_le_bytes());
+    bytes.extend(std::iter::repeat_n(0u8, 768 * wide * 2));
+    bytes.extend(std::iter::repeat_n(255i16.to_le_bytes(), wide).flatten());
+    bytes.extend(std::iter::repeat_n(i16::MAX.to_le_bytes(), 2 * wide).flatten());
+    bytes.extend_from_slice(&0i32.to_le_bytes());
+    let saturated = Network::from_bytes(&bytes).unwrap();
+    assert_eq!(saturated.evaluate(&saturated.refresh(&game), game.turn), 100_000);
+
+    // the search plays a legal move with it, and still finds the mate
+    let limits = SearchLimits { depth: Some(3), threads: Some(1), network: Some(Arc::new(network)), ..Default::default() };
+    let mv = Engine::search(&game, &limits).unwrap();
+    assert!(game.moves().unwrap().iter().any(|m| m.bits() == mv.bits()));
+    let game = fen::decode(BACK_RANK).unwrap();
+    assert_eq!(*Engine::search(&game, &limits).unwrap().bits(), BACK_RANK_MATE);
+}
+}
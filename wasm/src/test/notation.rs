@@ -0,0 +1,33 @@
+#[cfg(test)]
+mod test {
+use crate::game::{fen, pieces::Piece};
+
+#[test]
+fn test_uci_and_hash() {
+    use crate::game::{notation::{to_uci, from_uci}, perft::{perft, PerftTable}};
+
+    let game = fen::decode("rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8").unwrap();
+    for m in game.moves().unwrap() {
+        let uci = to_uci(&game, &m).unwrap();
+        assert_eq!(from_uci(&game, &uci).unwrap().bits(), m.bits());
+    }
+    assert!(from_uci(&game, "d7c8n").is_ok());
+    assert!(from_uci(&game, "e1g1").is_ok());
+
+    let game = fen::decode("r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1").unwrap();
+    assert!(from_uci(&game, "e1g1").is_err());
+
+    // both move orders reach the same position
+    let mut a = fen::decode("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+    let mut b = a.clone();
+    let start = a.hash();
+    for uci in ["g1f3", "g8f6", "b1c3", "b8c6"] { let m = from_uci(&a, uci).unwrap(); a.move_piece(*m.bits()); }
+    for uci in ["b1c3", "b8c6", "g1f3", "g8f6"] { let m = from_uci(&b, uci).unwrap(); b.move_piece(*m.bits()); }
+    assert_eq!(a.hash(), b.hash());
+    assert_ne!(a.hash(), start);
+
+    let table = PerftTable::new(1);
+    assert_eq!(perft(&game, 3, Some(&table)).unwrap(), 9_467);
+    assert_eq!(perft(&game, 3, Some(&table)).unwrap(), 9_467);
+}
+}
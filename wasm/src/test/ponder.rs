@@ -0,0 +1,57 @@
+#[cfg(test)]
+mod test {
+use crate::{engine::Engine, game::{fen, pieces::Piece}, test::test::KIWIPETE};
+
+#[test]
+fn test_ponder() {
+    use std::{sync::Arc, thread};
+    use crate::engine::{Ponder, SearchLimits, TimeControl, TranspositionTable};
+
+    let game = fen::decode(KIWIPETE).unwrap();
+    let limits = SearchLimits { depth: Some(4), threads: Some(1), ..Default::default() };
+
+    // the expected reply is a move of the position after the best move
+    let result = Engine::analyse(&game, &limits).unwrap();
+    let mut after = game.clone();
+    after.move_piece(*result.best_move.bits());
+    let reply = result.ponder.unwrap();
+    assert!(after.moves().unwrap().iter().any(|mv| mv.bits() == reply.bits()));
+
+    // a table searched before makes the same depth cheaper
+    let table = Arc::new(TranspositionTable::new(1));
+    let warm = SearchLimits { table: Some(table.clone()), ..limits.clone() };
+    let cold = Engine::analyse(&game, &warm).unwrap().nodes;
+    assert!(Engine::analyse(&game, &warm).unwrap().nodes < cold);
+
+    // the table's root entry shows how deep a search in the background has got
+    let progress = |handle: &thread::JoinHandle<_>, table: &TranspositionTable| {
+        while !handle.is_finished() && table.probe(game.hash()).is_none_or(|e| e.depth < 3) {
+            thread::yield_now();
+        }
+    };
+
+    // pondering outlasts a clock that has already run out, only the hit plays the move
+    let (ponder, table) = (Arc::new(Ponder::new(true)), Arc::new(TranspositionTable::new(1)));
+    let limits = SearchLimits {
+        time: Some(TimeControl::default()),
+        threads: Some(1),
+        ponder: Some(ponder.clone()),
+        table: Some(table.clone()),
+        ..Default::default()
+    };
+    let handle = { let game = game.clone(); thread::spawn(move || Engine::analyse(&game, &limits)) };
+    progress(&handle, &table);
+    assert!(!handle.is_finished());
+    ponder.hit();
+    assert!(handle.join().unwrap().unwrap().depth >= 2);
+
+    // a miss stops it with whatever it had
+    let (ponder, table) = (Arc::new(Ponder::new(true)), Arc::new(TranspositionTable::new(1)));
+    let limits = SearchLimits { threads: Some(1), ponder: Some(ponder.clone()), table: Some(table.clone()), ..Default::default() };
+    let handle = { let game = game.clone(); thread::spawn(move || Engine::analyse(&game, &limits)) };
+    progress(&handle, &table);
+    assert!(!handle.is_finished());
+    ponder.stop();
+    assert!(handle.join().unwrap().unwrap().depth >= 2);
+}
+}
@@ -0,0 +1,171 @@
+#[cfg(test)]
+mod test {
+use crate::{
+    engine::Engine,
+    game::{fen, pieces::Piece},
+    test::test::{BACK_RANK, BACK_RANK_MATE, KIWIPETE},
+};
+
+#[test]
+fn test_search_threads() {
+    use crate::engine::SearchLimits;
+
+    // one thread always plays the same move
+    let game = fen::decode(KIWIPETE).unwrap();
+    let single = SearchLimits { depth: Some(2), threads: Some(1), ..Default::default() };
+    let first = Engine::search(&game, &single).unwrap();
+    for _ in 0..3 {
+        assert_eq!(Engine::search(&game, &single).unwrap().bits(), first.bits());
+    }
+
+    let shared = SearchLimits { depth: Some(2), threads: Some(4), ..Default::default() };
+    let mv = Engine::search(&game, &shared).unwrap();
+    assert!(game.moves().unwrap().iter().any(|m| m.bits() == mv.bits()));
+
+    // mate in one is found whatever the thread count
+    let game = fen::decode(BACK_RANK).unwrap();
+    for threads in [1, 4] {
+        let mv = Engine::search(&game, &SearchLimits { depth: Some(2), threads: Some(threads), ..Default::default() }).unwrap();
+        assert_eq!(*mv.bits(), BACK_RANK_MATE);
+    }
+}
+
+#[test]
+fn test_negamax() {
+    use std::sync::atomic::{AtomicBool, AtomicU64};
+    use crate::engine::{evaluate, negamax, EngineConfig, Search, Selectivity, TranspositionTable, INFINITY, MATE};
+    use crate::game::Game;
+
+    // every move searched with the full window, no table
+    fn plain(game: &Game, depth: u8, ply: i32) -> i32 {
+        let (moves, info) = game.moves_verbose().unwrap();
+        if info.checkmate { return -MATE + ply; }
+        if info.stalemate || game.half_moves >= 100 { return 0; }
+        if depth == 0 { return evaluate(game, &info, &EngineConfig::default()); }
+        moves.iter().map(|m| {
+            let mut node = game.clone();
+            node.move_piece(*m.bits());
+            -plain(&node, depth - 1, ply + 1)
+        }).max().unwrap()
+    }
+
+    for fen in [
+        KIWIPETE,
+        "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+        BACK_RANK,
+    ] {
+        let game = fen::decode(fen).unwrap();
+        let (stop, tt, nodes) = (AtomicBool::new(false), TranspositionTable::new(1), AtomicU64::new(0));
+        for depth in 1..=3 {
+            let search = Search {
+                deadline: None,
+                stop: &stop,
+                tt: &tt,
+                selectivity: Selectivity::NONE,
+                depth,
+                nodes: &nodes,
+                max_nodes: None,
+                noise: 0,
+                seed: 0,
+                config: EngineConfig::default(),
+                network: None,
+                ponder: None,
+            };
+            assert_eq!(negamax(&game, None, depth, 0, -INFINITY, INFINITY, true, &search).unwrap(), plain(&game, depth, 0), "{} depth {}", fen, depth);
+        }
+    }
+}
+
+#[test]
+fn test_selectivity() {
+    use crate::engine::{SearchLimits, Selectivity};
+
+    // each part of the selective search alone still finds the mates
+    let all = Selectivity::NONE;
+    let parts = [
+        Selectivity { null_move: true, ..all },
+        Selectivity { late_move_reductions: true, ..all },
+        Selectivity { futility: true, ..all },
+        Selectivity { reverse_futility: true, ..all },
+        Selectivity { razoring: true, ..all },
+        Selectivity { check_extensions: true, ..all },
+        Selectivity::default(),
+        all,
+    ];
+    for (fen, mv) in [
+        (BACK_RANK, BACK_RANK_MATE),
+        ("r1bqkb1r/pppp1ppp/2n2n2/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 4 4", 1 << 39 | 1 << 53),
+    ] {
+        let game = fen::decode(fen).unwrap();
+        for selectivity in parts {
+            let limits = SearchLimits { depth: Some(3), threads: Some(1), selectivity, ..Default::default() };
+            assert_eq!(*Engine::search(&game, &limits).unwrap().bits(), mv, "{} {:?}", fen, selectivity);
+        }
+    }
+}
+
+#[test]
+fn test_mate_score() {
+    use crate::engine::{Score, SearchLimits};
+
+    // the nearest mate is played and counted in moves
+    for (fen, depth, mate) in [
+        (BACK_RANK, 2, 1),
+        ("6k1/5ppp/8/8/8/8/1R6/R5K1 w - - 0 1", 3, 1),
+        ("k7/8/1K6/8/8/8/8/7R w - - 0 1", 4, 1),
+        ("7k/8/5K2/8/8/8/8/1R6 w - - 0 1", 4, 2),
+        ("3r4/8/8/8/8/8/2k5/K7 w - - 0 1", 3, -1),
+    ] {
+        let game = fen::decode(fen).unwrap();
+        let limits = SearchLimits { depth: Some(depth), threads: Some(1), ..Default::default() };
+        let result = Engine::analyse(&game, &limits).unwrap();
+        assert_eq!(result.score.mate(), Some(mate), "{}", fen);
+    }
+
+    assert_eq!(Score::Mate(-2).to_string(), "mate -2");
+    assert_eq!(Score::Centipawns(100).to_string(), "cp 100");
+}
+
+#[test]
+fn test_skill() {
+    use crate::engine::{SearchLimits, Skill};
+
+    assert_eq!(Skill::from_elo(0), Skill::new(0));
+    assert_eq!(Skill::from_elo(1500), Skill::new(10));
+    assert_eq!(Skill::from_elo(9999), Skill::new(20));
+
+    // the lines come best first and the move played is one of them
+    let game = fen::decode(KIWIPETE).unwrap();
+    let limits = SearchLimits { depth: Some(3), threads: Some(1), multipv: 4, ..Default::default() };
+    let result = Engine::analyse(&game, &limits).unwrap();
+    assert_eq!(result.lines.len(), 4);
+    assert_eq!(result.lines[0].0.bits(), result.best_move.bits());
+    let cp = |s: &crate::engine::Score| match s { crate::engine::Score::Centipawns(cp) => *cp, _ => unreachable!() };
+    assert!(result.lines.windows(2).all(|w| cp(&w[0].1) >= cp(&w[1].1)));
+
+    // the first depth finishes whatever the limits
+    let starved = SearchLimits { nodes: Some(1), movetime: Some(std::time::Duration::ZERO), ..limits.clone() };
+    assert_eq!(Engine::analyse(&game, &starved).unwrap().depth, 1);
+
+    // the best line scores as it does searched alone
+    let single = SearchLimits { multipv: 1, ..limits };
+    assert_eq!(Engine::analyse(&game, &single).unwrap().score, result.score);
+
+    // the weakest level strays from the best move for some seeds
+    let mut played = std::collections::BTreeSet::new();
+    for seed in 0..16 {
+        let limits = SearchLimits { threads: Some(1), skill: Some(Skill::new(0)), seed, ..Default::default() };
+        let result = Engine::analyse(&game, &limits).unwrap();
+        assert!(result.depth <= 1 && result.nodes <= 100);
+        played.insert(*result.best_move.bits());
+    }
+    assert!(played.len() > 1);
+
+    // the full strength finds the mate whatever the seed
+    let game = fen::decode(BACK_RANK).unwrap();
+    for seed in 0..4 {
+        let limits = SearchLimits { depth: Some(2), threads: Some(1), skill: Some(Skill::new(20)), seed, ..Default::default() };
+        assert_eq!(*Engine::search(&game, &limits).unwrap().bits(), BACK_RANK_MATE);
+    }
+}
+}
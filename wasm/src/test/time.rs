@@ -0,0 +1,69 @@
+#[cfg(test)]
+mod test {
+use crate::{engine::Engine, game::fen};
+
+#[test]
+fn test_time_manager() {
+    use std::time::Duration;
+    use crate::engine::{evaluate::PAWN_VAL, SearchLimits, TimeControl, TimeManager};
+
+    let ms = Duration::from_millis;
+
+    // sudden death shares the clock over thirty moves, less the overhead
+    let manager = TimeManager::new(&TimeControl { time: ms(60_030), ..Default::default() });
+    assert_eq!(manager.soft(), ms(2000));
+    assert_eq!(manager.hard(), ms(60_000) * 4 / 33);
+
+    // the last move before the time control still leaves half the clock
+    let last = TimeControl { time: ms(10_030), moves_to_go: Some(1), ..Default::default() };
+    let manager = TimeManager::new(&last);
+    assert_eq!((manager.soft(), manager.hard()), (ms(5000), ms(5000)));
+
+    // three quarters of the increment is spent, all of the delay
+    let bonus = TimeControl { time: ms(60_030), increment: ms(1000), delay: ms(500), moves_to_go: None };
+    assert_eq!(TimeManager::new(&bonus).soft(), ms(2000 + 750 + 500));
+
+    // out of time, only the delay is left to think in
+    let flagging = TimeControl { time: ms(10), delay: ms(100), ..Default::default() };
+    assert_eq!(TimeManager::new(&flagging).hard(), ms(100));
+
+    // a single reply is played after one depth
+    let mut manager = TimeManager::new(&TimeControl { time: ms(60_000), ..Default::default() });
+    assert!(manager.next_depth(0, 0));
+    manager.single_reply();
+    assert!(!manager.next_depth(0, 0));
+
+    let clock = TimeControl { time: ms(60_000), ..Default::default() };
+    let game = fen::decode("k7/8/8/8/8/8/6r1/7K w - - 0 1").unwrap();
+    let result = Engine::analyse(&game, &SearchLimits { time: Some(clock), threads: Some(1), ..Default::default() }).unwrap();
+    assert_eq!(result.depth, 1);
+
+    // the soft limit stretches while the best move changes and shrinks once it holds
+    let mut manager = TimeManager::new(&TimeControl { time: ms(60_030), ..Default::default() });
+    let mut budgets = vec![];
+    for best in [0, 0, 1, 1, 1, 1, 1] {
+        manager.next_depth(best, 0);
+        budgets.push(manager.budget());
+    }
+    assert_eq!(budgets, [2000, 2400, 3200, 2400, 2000, 2000, 1400].map(ms));
+
+    // a falling score buys half again for each pawn up to two
+    let mut manager = TimeManager::new(&TimeControl { time: ms(60_030), ..Default::default() });
+    let mut budgets = vec![];
+    for score in [0, 0, -PAWN_VAL, -PAWN_VAL, -5 * PAWN_VAL] {
+        manager.next_depth(0, score);
+        budgets.push(manager.budget());
+    }
+    assert_eq!(budgets, [2000, 2400, 3000, 2000, 3400].map(ms));
+
+    // never past the hard limit, and an easy move cuts it to a quarter
+    let mut manager = TimeManager::new(&TimeControl { time: ms(60_030), moves_to_go: Some(5), ..Default::default() });
+    assert_eq!((manager.soft(), manager.hard()), (ms(12_000), ms(30_000)));
+    manager.next_depth(0, 0);
+    manager.next_depth(1, -10 * PAWN_VAL);
+    assert_eq!(manager.budget(), manager.hard());
+    manager.easy_move();
+    manager.next_depth(1, -10 * PAWN_VAL);
+    assert_eq!(manager.budget(), ms(3000));
+}
+}
@@ -0,0 +1,46 @@
+#[cfg(test)]
+mod test {
+use crate::game::fen;
+
+#[test]
+fn test_tune() {
+    use crate::engine::{tune::{decode_line, decode_pgn, quiesce, to_rust, Tuner}, WEIGHTS};
+
+    // the weights file is exactly what the tuner writes
+    assert_eq!(to_rust(&WEIGHTS, "picked by hand, not tuned yet"), include_str!("../engine/weights.rs"));
+
+    let epd = decode_line(r#"4k3/8/8/8/8/8/3Q4/4K3 w - - c9 "1-0";"#).unwrap();
+    let fen = decode_line("4k3/8/8/8/8/8/3q4/4K3 w - - 0 1 [0.0]").unwrap();
+    let draw = decode_line("4k3/8/8/8/8/8/8/4K3 w - - 0 1 1/2-1/2").unwrap();
+    assert_eq!((epd.result, fen.result, draw.result), (1.0, 0.0, 0.5));
+    assert!(decode_line("4k3/8/8/8/8/8/8/4K3 w - - 0 1").is_err());
+
+    // the hanging queen is taken before the position is called quiet
+    let game = fen::decode("4k3/8/8/8/8/8/3q4/3RK3 w - - 0 1").unwrap();
+    let (_, quiet) = quiesce(&game, -i32::MAX, i32::MAX, 0, &WEIGHTS);
+    assert_eq!(fen::encode(&quiet).unwrap(), "4k3/8/8/8/8/8/3R4/4K3 b - - 0 1");
+
+    // the positions after the first eight plies of finished games
+    let pgn = "[Event \"?\"]\n[Result \"1-0\"]\n\n1. e4 e5 2. Nf3 Nc6 3. Bc4 Nf6 {two knights} (3... Bc5) \
+               4. Ng5 d5 5. exd5 Na5?? 6. Bb5+ c6 $2 1-0\n\n[Result \"*\"]\n\n1. d4 d5 2. c4 e6 3. Nc3 Nf6 4. Bg5 Be7 5. e3 *\n";
+    let (games, errors) = decode_pgn(pgn);
+    assert!(errors.is_empty());
+    assert_eq!(games.len(), 4);
+    assert!(games.iter().all(|s| s.result == 1.0));
+    assert_eq!(fen::encode(&games[3].game).unwrap(), "r1bqkb1r/pp3ppp/2p2n2/nB1Pp1N1/8/8/PPPP1PPP/RNBQK2R w KQkq - 0 7");
+
+    // a game that cannot be read is skipped, castling written with zeros is read
+    let pgn = "[Result \"1-0\"]\n\n1. e4 Ke5 1-0\n\n[Result \"1/2-1/2\"]\n\n\
+               1. e4 e5 2. Nf3 Nc6 3. Bc4 Bc5 4. 0-0 Nf6 5. d3 0-0 1/2-1/2\n";
+    let (games, errors) = decode_pgn(pgn);
+    assert_eq!(errors.iter().map(|(number, _)| *number).collect::<Vec<_>>(), [1]);
+    assert_eq!(games.len(), 2);
+    assert_eq!(fen::encode(&games[1].game).unwrap(), "r1bq1rk1/pppp1ppp/2n2n2/2b1p3/2B1P3/3P1N2/PPP2PPP/RNBQ1RK1 w - - 1 6");
+
+    // tuning never ends with a larger error than it started
+    let tuner = Tuner::new(vec![epd, fen, draw], &WEIGHTS);
+    let k = tuner.fit_k(&WEIGHTS);
+    let tuned = tuner.tune(&WEIGHTS, k, |_, _, _| ());
+    assert!(tuner.error(&tuned, k) <= tuner.error(&WEIGHTS, k));
+}
+}